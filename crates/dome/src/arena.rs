@@ -27,6 +27,10 @@ impl Arena {
         ViewId(id)
     }
 
+    pub fn view_ids(&self) -> impl Iterator<Item = ViewId> + '_ {
+        self.views.keys().copied()
+    }
+
     pub fn get_view(&self, id: ViewId) -> Option<&UiNode> {
         self.views.get(&id).map(|data| &data.view)
     }
@@ -52,25 +56,27 @@ impl Arena {
     }
 
     pub fn set_previuos_region(&mut self, id: ViewId, region: Region) {
-        self.views
-            .get_mut(&id)
-            .map(|data| data.prev_region = region);
+        if let Some(data) = self.views.get_mut(&id) {
+            data.prev_region = region;
+        }
     }
 
     pub fn set_relative_region(&mut self, id: ViewId, region: Region) {
-        self.views
-            .get_mut(&id)
-            .map(|data| data.relative_region = region);
+        if let Some(data) = self.views.get_mut(&id) {
+            data.relative_region = region;
+        }
     }
 
     pub fn set_real_region(&mut self, id: ViewId, region: Region) {
-        self.views
-            .get_mut(&id)
-            .map(|data| data.real_region = region);
+        if let Some(data) = self.views.get_mut(&id) {
+            data.real_region = region;
+        }
     }
 
     pub fn set_dirty(&mut self, id: ViewId, dirty: bool) {
-        self.views.get_mut(&id).map(|data| data.is_dirty = dirty);
+        if let Some(data) = self.views.get_mut(&id) {
+            data.is_dirty = dirty;
+        }
     }
 
     pub fn push_view(&mut self, view: UiNode) -> ViewId {
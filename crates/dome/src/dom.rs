@@ -1,7 +1,5 @@
-use std::collections::HashMap;
-
-use kreuz_ui::Scene;
-use kurbo::Rect;
+use kreuz_ui::{RootView, Scene, ViewEvent, ViewResponce};
+use kurbo::{Rect, Size};
 
 use super::*;
 
@@ -13,21 +11,108 @@ pub struct DocumentModel {
 }
 
 impl DocumentModel {
-    pub fn draw(&mut self, drawer: &mut Scene) {
-        let mut region_calc = RegionCalc {
-            arena: &mut self.arena,
-            text: &mut self.text,
-            calc_data: HashMap::new(),
-        };
+    /// Builds the view tree, regions are computed once the window size is known
+    pub fn new(view: impl View) -> Self {
+        let mut arena = Arena::default();
+        let root = view.build(&mut arena);
+        Self {
+            arena,
+            context: Context {
+                window_size: Size::ZERO,
+            },
+            text: SimpleText::new(),
+            root,
+        }
+    }
+
+    pub fn root(&self) -> ViewId {
+        self.root
+    }
+
+    pub fn arena(&self) -> &Arena {
+        &self.arena
+    }
+
+    pub fn arena_mut(&mut self) -> &mut Arena {
+        &mut self.arena
+    }
+
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    /// Fonts for the document, call `layout` after adding some
+    pub fn text_mut(&mut self) -> &mut SimpleText {
+        &mut self.text
+    }
+
+    pub fn layout(&mut self) {
+        let mut region_calc = RegionCalc::new(&mut self.arena, &mut self.text);
         region_calc.compute_regions(
             self.root,
             Rect::from_origin_size((0.0, 0.0), self.context.window_size),
         );
-        drop(region_calc);
+    }
 
-        let mut draw_ctx = DrawCtx::new(drawer, &mut self.context, &mut self.arena, &mut self.text);
+    pub fn draw(&self, drawer: &mut Scene) {
+        let mut draw_ctx = DrawCtx::new(drawer, &self.context, &self.arena, &self.text);
         draw_ctx.draw(self.root);
     }
 
-    pub fn process_event(&mut self, _event: Event) {}
+    /// Whether some view wants `Event::Update` on every frame
+    pub fn has_update_handlers(&self) -> bool {
+        self.arena.view_ids().any(|id| {
+            self.arena.get_view(id).is_some_and(|node| {
+                node.event_handlers
+                    .values()
+                    .any(|handler| matches!(handler, EventHandler::Update(_)))
+            })
+        })
+    }
+
+    pub fn process_event(&mut self, event: Event) {
+        match event {
+            Event::Update => {
+                for id in self.arena.view_ids() {
+                    let Some(node) = self.arena.get_view(id) else {
+                        continue;
+                    };
+                    for handler in node.event_handlers.values() {
+                        match handler {
+                            EventHandler::Update(handler) => handler(&mut self.context),
+                        }
+                    }
+                }
+            }
+            Event::Resize(size) => self.context.window_size = size,
+        }
+        self.layout();
+    }
+}
+
+/// Dome events can only represent `ViewEvent::Resize` and `ViewEvent::AnimationFrame`, update
+/// handlers are called on every animation frame while any of them is registered.
+///
+/// Everything else is skipped: views have no handlers for pointer, keyboard, IME or focus events
+/// yet, and skipping `ViewEvent::CloseRequested` lets the window close right away, as documents
+/// can't ask for a confirmation
+impl RootView for DocumentModel {
+    fn render(&self, scene: &mut Scene) {
+        self.draw(scene);
+    }
+
+    fn handle_event(&mut self, event: &ViewEvent) -> ViewResponce {
+        let event = match event {
+            ViewEvent::Resize { size, .. } => Event::Resize(*size),
+            ViewEvent::AnimationFrame { .. } => Event::Update,
+            ViewEvent::CloseRequested => return ViewResponce::Skipped,
+            _ => return ViewResponce::Skipped,
+        };
+        self.process_event(event);
+        if self.has_update_handlers() {
+            ViewResponce::Animate
+        } else {
+            ViewResponce::Repaint
+        }
+    }
 }
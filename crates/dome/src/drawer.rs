@@ -2,7 +2,7 @@ use std::f64::consts::PI;
 
 use kreuz_ui::Scene;
 use kurbo::{Affine, Arc, Line, Size, Stroke};
-use peniko::{BlendMode, BrushRef, Style};
+use peniko::{BrushRef, Mix};
 
 use super::*;
 
@@ -12,7 +12,7 @@ pub struct Context {
 
 pub struct DrawCtx<'a, 'b, 'c, 'd> {
     drawer: &'a mut Scene,
-    _context: &'b mut Context,
+    _context: &'b Context,
     arena: &'c Arena,
     text: &'d SimpleText,
 }
@@ -20,9 +20,9 @@ pub struct DrawCtx<'a, 'b, 'c, 'd> {
 impl<'a, 'b, 'c, 'd> DrawCtx<'a, 'b, 'c, 'd> {
     pub fn new(
         drawer: &'a mut Scene,
-        context: &'b mut Context,
+        context: &'b Context,
         arena: &'c Arena,
-        text: &'d mut SimpleText,
+        text: &'d SimpleText,
     ) -> Self {
         Self {
             drawer,
@@ -38,16 +38,16 @@ impl<'a, 'b, 'c, 'd> DrawCtx<'a, 'b, 'c, 'd> {
         let region = self.draw_decorations(&node.styles, region);
 
         match &node.entity {
-            Entity::Box(entity) => self.draw_box(&entity),
-            Entity::Stack(entity) => self.draw_stack(&entity),
-            Entity::Scroll(entity) => self.draw_scrollable(&entity, region),
-            Entity::Switch(entity) => self.draw_switchable(&entity),
-            Entity::Image(entity) => self.draw_image(&entity, region),
-            Entity::Rect(entity) => self.draw_rect(&entity, region),
-            Entity::Text(entity) => self.draw_text(&entity, region),
-            Entity::Paragraph(entity) => self.draw_paragraph(&entity, region),
-            Entity::Canvas(entity) => self.draw_canvas(&entity, region),
-            Entity::Scale(entity) => self.draw_scale(&entity, region),
+            Entity::Box(entity) => self.draw_box(entity),
+            Entity::Stack(entity) => self.draw_stack(entity),
+            Entity::Scroll(entity) => self.draw_scrollable(entity, region),
+            Entity::Switch(entity) => self.draw_switchable(entity),
+            Entity::Image(entity) => self.draw_image(entity, region),
+            Entity::Rect(entity) => self.draw_rect(entity, region),
+            Entity::Text(entity) => self.draw_text(entity, region),
+            Entity::Paragraph(entity) => self.draw_paragraph(entity, region),
+            Entity::Canvas(entity) => self.draw_canvas(entity, region),
+            Entity::Scale(entity) => self.draw_scale(entity, region),
         }
     }
 
@@ -92,14 +92,14 @@ impl<'a, 'b, 'c, 'd> DrawCtx<'a, 'b, 'c, 'd> {
 
         macro_rules! draw_line {
             ($orientation:ident, $x0:expr, $y0:expr, $x1:expr, $y1:expr) => {
-                if !disabled.$orientation {
-                    // self.drawer.stroke(
-                    //     &Stroke::new(width),
-                    //     Affine::IDENTITY,
-                    //     paint,
-                    //     None,
-                    //     &Line::new(($x0, $y0), ($x1, $y1)),
-                    // );
+                if width > 0.0 && !disabled.$orientation {
+                    self.drawer.stroke(
+                        &Stroke::new(width),
+                        Affine::IDENTITY,
+                        paint,
+                        None,
+                        &Line::new(($x0, $y0), ($x1, $y1)),
+                    );
                 }
             };
         }
@@ -134,29 +134,24 @@ impl<'a, 'b, 'c, 'd> DrawCtx<'a, 'b, 'c, 'd> {
         );
 
         macro_rules! draw_arc {
+            // Quarter of a circle around the corner's center, starting at `$angle`
             ($orientation:ident, $x0:expr, $y0:expr, $angle:expr) => {
-                if !disabled.$orientation {
-                    // self.drawer.stroke(
-                    //     &Stroke::new(width),
-                    //     Affine::IDENTITY,
-                    //     paint,
-                    //     None,
-                    //     &Arc::new(
-                    //         (region.x0 + radius, region.y0 + radius),
-                    //         (radius, radius),
-                    //         0.0,
-                    //         PI / 2.0,
-                    //         $angle,
-                    //     ),
-                    // );
+                if width > 0.0 && radius > 0.0 && !disabled.$orientation {
+                    self.drawer.stroke(
+                        &Stroke::new(width),
+                        Affine::IDENTITY,
+                        paint,
+                        None,
+                        &Arc::new(($x0, $y0), (radius, radius), $angle, PI / 2.0, 0.0),
+                    );
                 }
             };
         }
 
-        draw_arc!(lt, region.x0 + radius, region.y0 + radius, PI * 1.5);
-        draw_arc!(rt, region.x1 - radius, region.y0 + radius, 0.0);
-        draw_arc!(lb, region.x0 + radius, region.y1 - radius, PI);
-        draw_arc!(rb, region.x1 - radius, region.y1 - radius, PI / 2.0);
+        draw_arc!(lt, region.x0 + radius, region.y0 + radius, PI);
+        draw_arc!(rt, region.x1 - radius, region.y0 + radius, PI * 1.5);
+        draw_arc!(lb, region.x0 + radius, region.y1 - radius, PI / 2.0);
+        draw_arc!(rb, region.x1 - radius, region.y1 - radius, 0.0);
 
         if !disabled.left && !disabled.lt && !disabled.lb {
             region.x0 += borders.width / 2.0;
@@ -180,18 +175,17 @@ impl<'a, 'b, 'c, 'd> DrawCtx<'a, 'b, 'c, 'd> {
         border_radius: f64,
         region: Region,
     ) {
-        // self.drawer.fill(
-        //     peniko::Fill::NonZero,
-        //     Affine::IDENTITY,
-        //     background,
-        //     None,
-        //     &region.to_rounded_rect(border_radius),
-        // );
+        self.drawer.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            background,
+            None,
+            &region.to_rounded_rect(border_radius),
+        );
     }
 
     fn draw_box(&mut self, entity: &BoxEntity) {
-        let id = entity.inner.clone();
-        self.draw(id);
+        self.draw(entity.inner);
     }
 
     fn draw_stack(&mut self, entity: &StackEntity) {
@@ -202,88 +196,88 @@ impl<'a, 'b, 'c, 'd> DrawCtx<'a, 'b, 'c, 'd> {
     }
 
     fn draw_scrollable(&mut self, entity: &ScrollEntity, region: Region) {
-        let id = entity.inner.clone();
-        // self.drawer
-        //     .push_layer(peniko::Mix::Clip, 1.0, Affine::IDENTITY, &region);
-        self.draw(id);
-        // self.drawer.pop_layer();
+        self.drawer
+            .push_layer(Mix::Clip, 1.0, Affine::IDENTITY, &region);
+        self.draw(entity.inner);
+        self.drawer.pop_layer();
     }
 
     fn draw_switchable(&mut self, entity: &SwitchEntity) {
-        let id = entity.inner[entity.mode].clone();
-        self.draw(id);
+        self.draw(entity.inner[entity.mode]);
     }
 
     fn draw_image(&mut self, entity: &ImageEntity, region: Region) {
         let image = &entity.image;
-        let affine = Affine::translate(region.origin().to_vec2()).then_scale_non_uniform(
-            image.width as f64 / region.width(),
-            image.height as f64 / region.height(),
-        );
-        // self.drawer.draw_image(&entity.image, affine);
+        // Stretched over the region
+        let affine = Affine::translate(region.origin().to_vec2())
+            * Affine::scale_non_uniform(
+                region.width() / image.width as f64,
+                region.height() / image.height as f64,
+            );
+        self.drawer.draw_image(image, affine);
     }
 
     fn draw_rect(&mut self, entity: &RectEntity, region: Region) {
-        // self.drawer.fill(
-        //     Fill::EvenOdd,
-        //     Affine::IDENTITY,
-        //     &entity.paint,
-        //     None,
-        //     &region.to_rounded_rect(entity.radii),
-        // );
+        self.drawer.fill(
+            Fill::EvenOdd,
+            Affine::IDENTITY,
+            &entity.paint,
+            None,
+            &region.to_rounded_rect(entity.radii),
+        );
     }
 
     fn draw_text(&mut self, entity: &TextEntity, region: Region) {
-        // let text_run = self.text.make_font_run(entity.styles.size as _, None);
-        // let (mut x, y) = region.origin().into();
-        // let space_width = text_run.get_char_data(' ').width;
-        // entity.text.split_whitespace().for_each(|word| {
-        //     x += text_run.draw_word(
-        //         &mut self.drawer,
-        //         &entity.styles.color,
-        //         &Style::Fill(Fill::NonZero),
-        //         Affine::translate((x, y)),
-        //         word,
-        //     ) as f64
-        //         + space_width as f64;
-        // });
+        let text_run = self.text.make_font_run(&entity.styles, None);
+        let layout = text_run.wrap(&entity.text, WritingDirection::Natural, f32::INFINITY, 0.0);
+        text_run.draw_layout(
+            self.drawer,
+            entity.styles.color,
+            Fill::NonZero,
+            Affine::translate(region.origin().to_vec2()),
+            &layout,
+            TextAlign::Left,
+            region.width() as f32,
+        );
     }
 
     fn draw_paragraph(&mut self, entity: &ParagraphEntity, region: Region) {
-        // let text_run = self.text.make_font_run(entity.styles.size as _, None);
-        // let (mut x, mut y) = region.origin().into();
-        // let line_height = text_run.get_line_height() as f64;
-        // let space_width = text_run.get_char_data(' ').width as f64;
-        // entity.text.split_whitespace().for_each(|word| {
-        //     if x + text_run.get_word_width(word) as f64 >= region.x1 {
-        //         x = region.origin().x;
-        //         y += line_height;
-        //     }
-        //     x += text_run.draw_word(
-        //         &mut self.drawer,
-        //         &entity.styles.color,
-        //         &Style::Fill(Fill::NonZero),
-        //         Affine::translate((x, y)),
-        //         word,
-        //     ) as f64
-        //         + space_width;
-        // });
+        let ParagraphStyles {
+            offset,
+            align,
+            v_align,
+            direction,
+        } = entity.extended_styles;
+        let text_run = self.text.make_font_run(&entity.styles, None);
+        let layout = text_run.wrap(
+            &entity.text,
+            direction,
+            region.width() as f32,
+            offset as f32,
+        );
+        let height = layout.lines.len() as f64 * text_run.get_line_height() as f64;
+        let y = match v_align {
+            VAlign::Top => region.y0,
+            VAlign::Center => region.y0 + (region.height() - height) / 2.0,
+            VAlign::Bottom => region.y1 - height,
+        };
+        text_run.draw_layout(
+            self.drawer,
+            entity.styles.color,
+            Fill::NonZero,
+            Affine::translate((region.x0, y)),
+            &layout,
+            align,
+            region.width() as f32,
+        );
     }
 
     fn draw_canvas(&mut self, entity: &CanvasEntity, region: Region) {
         (entity.draw)(self, region);
     }
 
-    fn draw_scale(&mut self, entity: &ScaleEntity, region: Region) {
-        let id = entity.inner.clone();
-        let scale = entity.scale.clone();
-        // self.drawer.push_layer(
-        //     BlendMode::new(peniko::Mix::Normal, peniko::Compose::DestAtop),
-        //     1.0,
-        //     Affine::scale(scale),
-        //     &region,
-        // );
-        self.draw(id);
-        // self.drawer.pop_layer();
+    // TODO: scale the inner view, regions of its subtree are computed unscaled
+    fn draw_scale(&mut self, entity: &ScaleEntity, _region: Region) {
+        self.draw(entity.inner);
     }
 }
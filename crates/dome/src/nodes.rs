@@ -127,6 +127,7 @@ pub struct ParagraphStyles {
     pub offset: f64,
     pub align: TextAlign,
     pub v_align: VAlign,
+    pub direction: WritingDirection,
 }
 
 #[derive(Clone, Copy)]
@@ -137,6 +138,18 @@ pub enum TextAlign {
     Justify,
 }
 
+/// Base direction of paragraphs, as glazier's `WritingDirection`
+#[derive(Default, Clone, Copy)]
+pub enum WritingDirection {
+    LeftToRight,
+    RightToLeft,
+    /// Taken from the first strong character of each paragraph
+    #[default]
+    Natural,
+}
+
+pub type CanvasDraw = Box<dyn Fn(&mut DrawCtx, Region)>;
+
 pub struct CanvasEntity {
-    pub draw: Box<dyn Fn(&mut DrawCtx, Region)>,
+    pub draw: CanvasDraw,
 }
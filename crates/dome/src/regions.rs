@@ -26,52 +26,20 @@ impl<'a, 'b> RegionCalc<'a, 'b> {
     }
 
     fn get_paragraph_size(&self, entity: &ParagraphEntity, max_width: f64) -> Region {
-        // let text_run = self.text.make_font_run(entity.styles.size as f32, None);
-        // let line_height = text_run.get_line_height();
-        // let space_width = text_run.get_char_data(' ').width;
-        // let height = entity
-        //     .text
-        //     .lines()
-        //     .map(|line| {
-        //         line.split_whitespace()
-        //             .fold((0.0, line_height), |(x, height), word| {
-        //                 let width = text_run.get_word_width(word);
-        //                 if x + width > max_width as _ {
-        //                     (width + space_width, height + line_height)
-        //                 } else {
-        //                     (x + width + space_width, height)
-        //                 }
-        //             })
-        //             .1
-        //     })
-        //     .fold(0.0, |acc, e| acc + e);
-        // Region::new(0.0, 0.0, max_width, height as _)
-        Default::default()
+        let text_run = self.text.make_font_run(&entity.styles, None);
+        let ParagraphStyles {
+            offset, direction, ..
+        } = entity.extended_styles;
+        let layout = text_run.wrap(&entity.text, direction, max_width as f32, offset as f32);
+        let height = layout.lines.len() as f32 * text_run.get_line_height();
+        Region::new(0.0, 0.0, max_width, height as _)
     }
 
     fn get_text_blob_size(&self, entity: &TextEntity) -> Region {
-        // let text_run = self.text.make_font_run(entity.styles.size as f32, None);
-        // let line_height = text_run.get_line_height();
-        // let space_width = text_run.get_char_data(' ').width;
-        // let (max_width, height) = entity
-        //     .text
-        //     .lines()
-        //     .map(|line| {
-        //         line.split_whitespace().fold(0.0, |x, word| {
-        //             let width = text_run.get_word_width(word);
-
-        //             if x != 0.0 {
-        //                 x + width + space_width
-        //             } else {
-        //                 width
-        //             }
-        //         })
-        //     })
-        //     .fold((0.0f32, 0.0), |(max_width, height), e| {
-        //         (max_width.max(e), height + line_height)
-        //     });
-        // Region::new(0.0, 0.0, max_width as _, height as _)
-        Default::default()
+        let text_run = self.text.make_font_run(&entity.styles, None);
+        let layout = text_run.wrap(&entity.text, WritingDirection::Natural, f32::INFINITY, 0.0);
+        let height = layout.lines.len() as f32 * text_run.get_line_height();
+        Region::new(0.0, 0.0, layout.width() as _, height as _)
     }
 
     pub fn compute_regions(&mut self, root: ViewId, bounds: Region) -> Option<()> {
@@ -118,18 +86,18 @@ impl<'a, 'b> RegionCalc<'a, 'b> {
 
         match &node.entity {
             Entity::Box(entity) => {
-                let id = entity.inner.clone();
+                let id = entity.inner;
                 self.compute_region(id, inner_region);
             }
             Entity::Scroll(entity) => {
-                let id = entity.inner.clone();
-                let offset = entity.offset.clone();
+                let id = entity.inner;
+                let offset = entity.offset;
                 inner_region.x0 -= offset.x;
                 inner_region.y0 -= offset.y;
                 self.compute_region(id, inner_region);
             }
             Entity::Switch(entity) => {
-                let id = entity.inner[entity.mode].clone();
+                let id = entity.inner[entity.mode];
                 self.compute_region(id, inner_region);
             }
             Entity::Stack(entity) => {
@@ -348,25 +316,25 @@ impl<'a, 'b> RegionCalc<'a, 'b> {
                     }
                 }
                 Entity::Scroll(entity) => {
-                    let id = entity.inner.clone();
+                    let id = entity.inner;
                     let h_scroll_enabled = entity.h_enabled;
                     if h_scroll_enabled {
                         self.recompute_paragraph_size_with_width(&vec![id], width);
                     }
                 }
                 Entity::Box(entity) => {
-                    let id = entity.inner.clone();
+                    let id = entity.inner;
                     self.recompute_paragraph_size_with_width(&vec![id], width);
                 }
                 Entity::Stack(entity) => {
-                    let direction = entity.direction.clone();
+                    let direction = entity.direction;
                     if direction != StackDirection::X {
                         let ids = entity.inner.clone();
                         self.recompute_paragraph_size_with_width(&ids, width);
                     }
                 }
                 Entity::Switch(entity) => {
-                    let id = entity.inner[entity.mode].clone();
+                    let id = entity.inner[entity.mode];
                     self.recompute_paragraph_size_with_width(&vec![id], width);
                 }
                 _ => (),
@@ -405,12 +373,12 @@ impl<'a, 'b> RegionCalc<'a, 'b> {
 
         let min_size = match &self.arena.get_view(node)?.entity {
             Entity::Box(entity) => {
-                let id = entity.inner.clone();
+                let id = entity.inner;
                 self.compute_size_constraints(id);
                 self.calc_data.get(&id)?.min_size
             }
             Entity::Scroll(entity) => {
-                let id = entity.inner.clone();
+                let id = entity.inner;
                 is_scrollable = !entity.enable_inner_min_size;
                 self.compute_size_constraints(id);
                 if is_scrollable {
@@ -421,11 +389,11 @@ impl<'a, 'b> RegionCalc<'a, 'b> {
             }
             Entity::Stack(entity) => {
                 let nodes = entity.inner.clone();
-                let direction = entity.direction.clone();
+                let direction = entity.direction;
                 self.compute_stack_size_constraints(direction, nodes)
             }
             Entity::Switch(entity) => {
-                let id = entity.inner[entity.mode].clone();
+                let id = entity.inner[entity.mode];
                 self.compute_size_constraints(id);
                 self.calc_data.get(&id)?.min_size
             }
@@ -549,18 +517,14 @@ fn rect_size(rect_radii: &RoundedRectRadii) -> Size {
 }
 
 fn sum_max_size(width: Option<f64>, border: f64) -> Option<f64> {
-    if let Some(width) = width {
-        Some(width + border)
-    } else {
-        None
-    }
+    width.map(|width| width + border)
 }
 
 fn sum_min_size(width: Option<f64>, border: f64) -> Option<f64> {
     Some(border + width.unwrap_or(0.0))
 }
 
-fn text_height(text: &String, styles: &TextStyles) -> f64 {
+fn text_height(text: &str, styles: &TextStyles) -> f64 {
     let mut lines = 1;
     for ch in text.chars() {
         if ch == '\n' {
@@ -159,7 +159,7 @@ pub struct Borders {
     pub disabled: BordersMode,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Default, Clone, Copy)]
 pub struct BordersMode {
     pub top: bool,
     pub right: bool,
@@ -170,18 +170,3 @@ pub struct BordersMode {
     pub rb: bool,
     pub lb: bool,
 }
-
-impl Default for BordersMode {
-    fn default() -> Self {
-        Self {
-            top: false,
-            right: false,
-            bottom: false,
-            left: false,
-            lt: false,
-            rt: false,
-            rb: false,
-            lb: false,
-        }
-    }
-}
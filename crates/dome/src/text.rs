@@ -1,99 +1,323 @@
-// use kreuz_ui::{
-//     skrifa::{
-//         self,
-//         charmap::Charmap,
-//         instance::Location,
-//         metrics::{GlyphMetrics, Metrics},
-//         prelude::Size as SkrifaSize,
-//         raw::FileRef,
-//         FontRef, GlyphId, MetadataProvider,
-//     },
-//     Glyph, Scene,
-// };
-// use kurbo::Affine;
-// use peniko::{Blob, Brush, BrushRef, Color, Fill, Font, StyleRef};
-// use std::sync::Arc;
-
-// // This is very much a hack to get things working.
-// // On Windows, can set this to "c:\\Windows\\Fonts\\seguiemj.ttf" to get color emoji
-// const ROBOTO_FONT: &[u8] = include_bytes!("../assets/roboto/Roboto-Regular.ttf");
-// const INCONSOLATA_FONT: &[u8] = include_bytes!("../assets/inconsolata/Inconsolata.ttf");
-// const NOTO_EMOJI_CBTF_SUBSET: &[u8] =
-//     include_bytes!("../assets/noto_color_emoji/NotoColorEmoji-CBTF-Subset.ttf");
-// const NOTO_EMOJI_COLR_SUBSET: &[u8] =
-//     include_bytes!("../assets/noto_color_emoji/NotoColorEmoji-Subset.ttf");
+use kreuz_ui::{Glyph, GlyphRun, Scene};
+use kurbo::Affine;
+use peniko::{Blob, Brush, BrushRef, Font, Style, StyleRef};
+use rustybuzz::{ttf_parser::Tag, Direction, UnicodeBuffer};
+use self_cell::self_cell;
+use skrifa::{
+    charmap::Charmap,
+    instance::{LocationRef, Size as SkrifaSize},
+    metrics::{GlyphMetrics, Metrics},
+    raw::FileRef,
+    FontRef, GlyphId, MetadataProvider,
+};
+use std::{collections::HashMap, ops::Range, sync::Arc};
+use unicode_bidi::{BidiInfo, Level};
+use unicode_linebreak::{linebreaks, BreakOpportunity};
+use unicode_script::{Script, UnicodeScript};
 
+use crate::{TextAlign, TextStyles, WritingDirection};
+
+const ROBOTO_FONT: &[u8] = include_bytes!("../assets/roboto/Roboto-Regular.ttf");
+const INCONSOLATA_FONT: &[u8] = include_bytes!("../assets/inconsolata/Inconsolata.ttf");
+// Subsets with ✅, 👀, 🎉 and 🤠 only, to keep the repository small
+const NOTO_EMOJI_COLR_SUBSET: &[u8] =
+    include_bytes!("../assets/noto_color_emoji/NotoColorEmoji-Subset.ttf");
+const NOTO_EMOJI_CBTF_SUBSET: &[u8] =
+    include_bytes!("../assets/noto_color_emoji/NotoColorEmoji-CBTF-Subset.ttf");
+
+const DEFAULT_FAMILY: &str = "Roboto";
+
+/// Fonts by family name, `TextStyles::font_family` picks one of them. Characters missing in the
+/// family font are taken from the fallback fonts, in the order they were added. None of the
+/// bundled fonts covers Arabic, Hebrew or Indic scripts, apps add fallbacks for them
 pub struct SimpleText {
-    // roboto: Font,
-    // inconsolata: Font,
-    // noto_emoji_colr_subset: Font,
-    // noto_emoji_cbtf_subset: Font,
+    families: HashMap<String, LoadedFont>,
+    fallbacks: Vec<LoadedFont>,
 }
-/*
+
+type Shaper<'a> = Option<rustybuzz::Face<'a>>;
+
+self_cell!(
+    /// Font with its shaping tables, which are parsed once when the font is added
+    struct LoadedFont {
+        owner: Font,
+
+        #[covariant]
+        dependent: Shaper,
+    }
+);
+
+/// Measures, shapes and draws text of a single family and size
 pub struct TextRunner<'a> {
-    font: &'a Font,
+    /// The family font followed by the fallbacks
+    faces: Vec<FontFace<'a>>,
     size: f32,
     glyph_transform: Option<Affine>,
-    var_loc: Arc<Location>,
-    charmap: Charmap<'a>,
-    line_height: f32,
-    glyph_metrics: GlyphMetrics<'a>,
+    metrics: Metrics,
+}
+
+/// Fonts that fail to parse are kept with no glyphs, so that indices match the fallback chain
+struct FontFace<'a> {
+    font: &'a Font,
+    charmap: Option<Charmap<'a>>,
+    glyph_metrics: Option<GlyphMetrics<'a>>,
+    shaper: Option<&'a rustybuzz::Face<'a>>,
 }
 
 pub struct GlyphData {
+    /// Index of the font in the fallback chain, 0 is the family font
+    pub face: usize,
     pub gid: GlyphId,
     pub width: f32,
 }
 
+/// Text broken into lines, with bidi levels resolved for every paragraph
+pub struct TextLayout<'t> {
+    text: &'t str,
+    bidi: BidiInfo<'t>,
+    pub lines: Vec<TextLine>,
+}
+
+/// Line of wrapped text
+pub struct TextLine {
+    /// Bytes of the text on the line, without trailing whitespace
+    pub range: Range<usize>,
+    /// Width of the text on the line
+    pub width: f32,
+    /// Indentation at the start of the line, on the right for RTL paragraphs
+    pub indent: f32,
+    /// Ends with a mandatory break or the end of the text, such lines are not justified
+    pub hard_break: bool,
+    /// Belongs to a right-to-left paragraph
+    pub rtl: bool,
+}
+
+/// Part of a line with a single font, script and direction
+struct TextItem {
+    range: Range<usize>,
+    face: usize,
+    script: Script,
+}
+
+struct ShapedGlyph {
+    id: u32,
+    /// Byte of the text the glyph was made of
+    cluster: usize,
+    advance: f32,
+    x_offset: f32,
+    y_offset: f32,
+}
+
+impl TextLine {
+    fn new(start: usize, indent: f32) -> Self {
+        Self {
+            range: start..start,
+            width: 0.0,
+            indent,
+            hard_break: false,
+            rtl: false,
+        }
+    }
+
+    /// Horizontal position of the line in a box of `max_width`, and the gap added after every
+    /// space of justified lines
+    fn align(&self, text: &str, align: TextAlign, max_width: f32) -> (f32, f32) {
+        let free = (max_width - self.indent - self.width).max(0.0);
+        let spaces = text[self.range.clone()].matches(' ').count();
+        let (shift, gap) = match align {
+            TextAlign::Left => (0.0, 0.0),
+            TextAlign::Right => (free, 0.0),
+            TextAlign::Center => (free / 2.0, 0.0),
+            TextAlign::Justify if !self.hard_break && spaces > 0 && free.is_finite() => {
+                (0.0, free / spaces as f32)
+            }
+            TextAlign::Justify if self.rtl && free.is_finite() => (free, 0.0),
+            TextAlign::Justify => (0.0, 0.0),
+        };
+        let x = if self.rtl { shift } else { shift + self.indent };
+        (x, gap)
+    }
+}
+
+impl<'t> TextLayout<'t> {
+    /// Width of the widest line, with indentation
+    pub fn width(&self) -> f32 {
+        self.lines
+            .iter()
+            .fold(0.0f32, |max, line| max.max(line.indent + line.width))
+    }
+}
+
 impl<'a> TextRunner<'a> {
     fn new(
-        font: &'a Font,
+        font: &'a LoadedFont,
+        fallbacks: &'a [LoadedFont],
         size: f32,
-        variations: &[(&str, f32)],
         glyph_transform: Option<Affine>,
     ) -> Self {
-        let font_ref = to_font_ref(font).unwrap();
-        let axes = font_ref.axes();
         let font_size = SkrifaSize::new(size);
-        let var_loc = Arc::new(axes.location(variations.iter().copied()));
-        let charmap = font_ref.charmap();
-        // TODO: dumb borrow checker, fix that later
-        let metrics = Metrics::new(&font_ref, font_size, unsafe {
-            std::mem::transmute::<_, &'static Location>(var_loc.as_ref())
-        });
-        let line_height = metrics.ascent - metrics.descent + metrics.leading;
-        let glyph_metrics = font_ref.glyph_metrics(font_size, unsafe {
-            std::mem::transmute::<_, &'static Location>(var_loc.as_ref())
-        });
+        // Line metrics come from the family font, so that fallbacks don't change line height
+        let metrics = to_font_ref(font.borrow_owner())
+            .map(|font_ref| font_ref.metrics(font_size, LocationRef::default()))
+            .unwrap_or_default();
+        let faces = std::iter::once(font)
+            .chain(fallbacks)
+            .map(|loaded| {
+                let font = loaded.borrow_owner();
+                let font_ref = to_font_ref(font);
+                FontFace {
+                    font,
+                    charmap: font_ref.as_ref().map(|font_ref| font_ref.charmap()),
+                    glyph_metrics: font_ref
+                        .map(|font_ref| font_ref.glyph_metrics(font_size, LocationRef::default())),
+                    shaper: loaded.borrow_dependent().as_ref(),
+                }
+            })
+            .collect();
         Self {
-            font,
+            faces,
             size,
             glyph_transform,
-            charmap,
-            line_height,
-            glyph_metrics,
-            var_loc,
+            metrics,
         }
     }
 
     pub fn get_line_height(&self) -> f32 {
-        self.line_height
+        self.metrics.ascent - self.metrics.descent + self.metrics.leading
     }
 
-    pub fn get_char_data(&self, ch: char) -> GlyphData {
-        let gid = self.charmap.map(ch).unwrap_or_default();
-        GlyphData {
+    /// Distance from the top of a line to its baseline
+    pub fn get_ascent(&self) -> f32 {
+        self.metrics.ascent
+    }
+
+    /// Glyph of the first font that has the character. Without such a font the family font's
+    /// `.notdef` is used, or nothing for invisible characters like variation selectors.
+    /// Shaping may pick other glyphs, this is for single characters out of context
+    pub fn get_char_data(&self, ch: char) -> Option<GlyphData> {
+        let found = self
+            .faces
+            .iter()
+            .enumerate()
+            .find_map(|(face, font)| Some((face, font.charmap.as_ref()?.map(ch)?)));
+        let (face, gid) = match found {
+            Some(found) => found,
+            None if is_invisible(ch) => return None,
+            None => (0, GlyphId::NOTDEF),
+        };
+        let width = self
+            .faces
+            .get(face)?
+            .glyph_metrics
+            .as_ref()
+            .and_then(|metrics| metrics.advance_width(gid));
+        Some(GlyphData {
+            face,
             gid,
-            width: self.glyph_metrics.advance_width(gid).unwrap_or_default(),
-        }
+            width: width.unwrap_or_default(),
+        })
     }
 
+    /// Width of the shaped text
     pub fn get_word_width(&self, word: &str) -> f32 {
-        word.chars()
-            .map(|ch| self.get_char_data(ch).width)
-            .fold(0.0, |res, width| res + width)
+        self.itemize(word, 0..word.len())
+            .iter()
+            .flat_map(|item| self.shape(word, item, None))
+            .fold(0.0, |res, glyph| res + glyph.advance)
     }
 
+    /// Breaks text into lines no wider than `max_width` at Unicode line break opportunities
+    /// (UAX #14), segments wider than that get a line of their own. The first line after every
+    /// mandatory break is indented by `offset`. Paragraph directions are resolved with UAX #9,
+    /// `Natural` takes it from the first strong character of each paragraph
+    pub fn wrap<'t>(
+        &self,
+        text: &'t str,
+        direction: WritingDirection,
+        max_width: f32,
+        offset: f32,
+    ) -> TextLayout<'t> {
+        let level = match direction {
+            WritingDirection::LeftToRight => Some(Level::ltr()),
+            WritingDirection::RightToLeft => Some(Level::rtl()),
+            WritingDirection::Natural => None,
+        };
+        let bidi = BidiInfo::new(text, level);
+
+        let mut lines = Vec::new();
+        let mut line = TextLine::new(0, offset);
+        // Pen position on the line, with trailing whitespace
+        let mut x = 0.0;
+        let mut start = 0;
+        for (end, opportunity) in linebreaks(text) {
+            let segment = &text[start..end];
+            let visible = start..start + segment.trim_end().len();
+            let width = self.get_word_width(&text[visible.clone()]);
+            if !line.range.is_empty() && line.indent + x + width > max_width {
+                lines.push(std::mem::replace(&mut line, TextLine::new(start, 0.0)));
+                x = 0.0;
+            }
+            if !visible.is_empty() {
+                line.range.end = visible.end;
+                line.width = x + width;
+            }
+            match opportunity {
+                BreakOpportunity::Mandatory => {
+                    line.hard_break = true;
+                    lines.push(std::mem::replace(&mut line, TextLine::new(end, offset)));
+                    x = 0.0;
+                }
+                BreakOpportunity::Allowed => x += self.get_word_width(segment),
+            }
+            start = end;
+        }
+
+        for line in &mut lines {
+            let paragraph = bidi
+                .paragraphs
+                .iter()
+                .find(|paragraph| paragraph.range.contains(&line.range.start))
+                .or(bidi.paragraphs.last());
+            line.rtl = paragraph.is_some_and(|paragraph| paragraph.level.is_rtl());
+        }
+        let mut layout = TextLayout { text, bidi, lines };
+        // Segments were measured on their own, kerning between them only shows up on the line
+        for index in 0..layout.lines.len() {
+            let width = self
+                .shape_line(&layout, &layout.lines[index])
+                .iter()
+                .fold(0.0, |width, (_, glyph)| width + glyph.advance);
+            layout.lines[index].width = width;
+        }
+        layout
+    }
+
+    /// Draws the lines with the top left corner of the text at `transform`. Alignment is
+    /// physical, but lines of RTL paragraphs that are not justified keep to the right
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_layout<'b>(
+        &self,
+        scene: &mut Scene,
+        brush: impl Into<BrushRef<'b>>,
+        style: impl Into<StyleRef<'b>>,
+        transform: Affine,
+        layout: &TextLayout,
+        align: TextAlign,
+        max_width: f32,
+    ) {
+        let brush = brush.into().to_owned();
+        let style = style.into().to_owned();
+        let line_height = self.get_line_height();
+        let mut baseline = self.get_ascent();
+        for line in &layout.lines {
+            let (x, gap) = line.align(layout.text, align, max_width);
+            let line_transform = transform * Affine::translate((x as f64, baseline as f64));
+            self.draw_line(scene, &brush, &style, line_transform, layout, line, gap);
+            baseline += line_height;
+        }
+    }
+
+    /// Draws the word with its baseline origin at `transform`, returns its width
     pub fn draw_word<'b>(
         &self,
         scene: &mut Scene,
@@ -101,219 +325,247 @@ impl<'a> TextRunner<'a> {
         style: impl Into<StyleRef<'b>>,
         transform: Affine,
         word: &str,
+    ) -> f32 {
+        let brush = brush.into().to_owned();
+        let style = style.into().to_owned();
+        let layout = self.wrap(word, WritingDirection::Natural, f32::INFINITY, 0.0);
+        layout.lines.iter().fold(0.0, |x, line| {
+            let line_transform = transform * Affine::translate((x as f64, 0.0));
+            x + self.draw_line(scene, &brush, &style, line_transform, &layout, line, 0.0)
+        })
+    }
+
+    /// Draws a glyph run per font of the fallback chain. `gap` is added after every space,
+    /// returns the width of the line
+    #[allow(clippy::too_many_arguments)]
+    fn draw_line(
+        &self,
+        scene: &mut Scene,
+        brush: &Brush,
+        style: &Style,
+        transform: Affine,
+        layout: &TextLayout,
+        line: &TextLine,
+        gap: f32,
     ) -> f32 {
         let mut pen_x = 0.0;
-        // scene
-        //     .draw_glyphs(self.font)
-        //     .font_size(self.size)
-        //     .transform(transform)
-        //     .glyph_transform(self.glyph_transform)
-        //     .normalized_coords(self.var_loc.coords())
-        //     .brush(brush.into())
-        //     .hint(false)
-        //     .draw(
-        //         style.into(),
-        //         word.chars().map(|ch| {
-        //             let GlyphData { gid, width } = self.get_char_data(ch);
-        //             let x = pen_x;
-        //             pen_x += width;
-        //             Glyph {
-        //                 id: gid.to_u32(),
-        //                 x,
-        //                 y: 0.0,
-        //             }
-        //         }),
-        //     );
+        let mut glyph_runs: Vec<(usize, Vec<Glyph>)> = Vec::new();
+        for (face, glyph) in self.shape_line(layout, line) {
+            let shaped = Glyph {
+                id: glyph.id,
+                x: pen_x + glyph.x_offset,
+                y: glyph.y_offset,
+            };
+            pen_x += glyph.advance;
+            if layout.text[glyph.cluster..].starts_with(' ') {
+                pen_x += gap;
+            }
+            match glyph_runs.last_mut() {
+                Some((run_face, glyphs)) if *run_face == face => glyphs.push(shaped),
+                _ => glyph_runs.push((face, vec![shaped])),
+            }
+        }
+
+        // Color glyphs ignore the brush
+        for (face, glyphs) in glyph_runs {
+            scene.draw_glyphs(GlyphRun {
+                font: self.faces[face].font.clone(),
+                font_size: self.size,
+                transform,
+                glyph_transform: self.glyph_transform,
+                normalized_coords: Vec::new(),
+                brush: brush.clone(),
+                style: style.clone(),
+                hint: false,
+                glyphs,
+            });
+        }
         pen_x
     }
+
+    /// Glyphs of the line in visual order, with the index of their font in the fallback chain
+    fn shape_line(&self, layout: &TextLayout, line: &TextLine) -> Vec<(usize, ShapedGlyph)> {
+        let text = layout.text;
+        if line.range.is_empty() {
+            return Vec::new();
+        }
+        let Some(paragraph) = layout
+            .bidi
+            .paragraphs
+            .iter()
+            .find(|paragraph| paragraph.range.contains(&line.range.start))
+        else {
+            return Vec::new();
+        };
+        let (levels, runs) = layout.bidi.visual_runs(paragraph, line.range.clone());
+
+        let mut glyphs = Vec::new();
+        for run in runs {
+            let direction = if levels[run.start].is_rtl() {
+                Direction::RightToLeft
+            } else {
+                Direction::LeftToRight
+            };
+            let mut items = self.itemize(text, run);
+            // Shaped RTL items are in visual order already, but follow each other right to left
+            if direction == Direction::RightToLeft {
+                items.reverse();
+            }
+            for item in items {
+                let shaped = self.shape(text, &item, Some(direction));
+                glyphs.extend(shaped.into_iter().map(|glyph| (item.face, glyph)));
+            }
+        }
+        glyphs
+    }
+
+    /// Splits the range in logical order where the script or the font of the fallback chain
+    /// changes. Common and inherited characters, like spaces and combining marks, stay with
+    /// the preceding item
+    fn itemize(&self, text: &str, range: Range<usize>) -> Vec<TextItem> {
+        let mut items: Vec<TextItem> = Vec::new();
+        for (index, ch) in text[range.clone()].char_indices() {
+            let start = range.start + index;
+            let end = start + ch.len_utf8();
+            let script = ch.script();
+            let neutral = matches!(script, Script::Common | Script::Inherited | Script::Unknown);
+            let face = match items.last() {
+                Some(item) if script == Script::Inherited || is_invisible(ch) => item.face,
+                _ => self.get_char_data(ch).map_or(0, |data| data.face),
+            };
+            if let Some(item) = items.last_mut() {
+                let same_script = neutral || item.script == script || item.script == Script::Common;
+                if face == item.face && same_script {
+                    item.range.end = end;
+                    if !neutral {
+                        item.script = script;
+                    }
+                    continue;
+                }
+            }
+            items.push(TextItem {
+                range: start..end,
+                face,
+                script: if neutral { Script::Common } else { script },
+            });
+        }
+        items
+    }
+
+    /// Glyphs of the item in visual order. Without a direction it's guessed from the script
+    fn shape(&self, text: &str, item: &TextItem, direction: Option<Direction>) -> Vec<ShapedGlyph> {
+        let Some(shaper) = self.faces.get(item.face).and_then(|face| face.shaper) else {
+            return Vec::new();
+        };
+
+        let mut buffer = UnicodeBuffer::new();
+        buffer.push_str(&text[item.range.clone()]);
+        if let Some(direction) = direction {
+            buffer.set_direction(direction);
+        }
+        if let Some(script) = to_shaper_script(item.script) {
+            buffer.set_script(script);
+        }
+        buffer.guess_segment_properties();
+        let output = rustybuzz::shape(shaper, &[], buffer);
+
+        let scale = self.size / shaper.units_per_em() as f32;
+        output
+            .glyph_infos()
+            .iter()
+            .zip(output.glyph_positions())
+            .map(|(info, position)| ShapedGlyph {
+                id: info.glyph_id,
+                cluster: item.range.start + info.cluster as usize,
+                advance: position.x_advance as f32 * scale,
+                x_offset: position.x_offset as f32 * scale,
+                // Font units go up, scene units go down
+                y_offset: -position.y_offset as f32 * scale,
+            })
+            .collect()
+    }
 }
 
 impl SimpleText {
-    #[allow(clippy::new_without_default)]
+    /// Bundled fonts: Roboto, also as `sans-serif`, Inconsolata, also as `monospace`, and
+    /// fallbacks to COLR and then bitmap Noto Color Emoji
     pub fn new() -> Self {
-        Self {
-            roboto: Font::new(Blob::new(Arc::new(ROBOTO_FONT)), 0),
-            inconsolata: Font::new(Blob::new(Arc::new(INCONSOLATA_FONT)), 0),
-            noto_emoji_colr_subset: Font::new(Blob::new(Arc::new(NOTO_EMOJI_COLR_SUBSET)), 0),
-            noto_emoji_cbtf_subset: Font::new(Blob::new(Arc::new(NOTO_EMOJI_CBTF_SUBSET)), 0),
-        }
+        let roboto = Font::new(Blob::new(Arc::new(ROBOTO_FONT)), 0);
+        let inconsolata = Font::new(Blob::new(Arc::new(INCONSOLATA_FONT)), 0);
+        let mut text = Self {
+            families: HashMap::new(),
+            fallbacks: Vec::new(),
+        };
+        text.add_family(DEFAULT_FAMILY, roboto.clone());
+        text.add_family("sans-serif", roboto);
+        text.add_family("Inconsolata", inconsolata.clone());
+        text.add_family("monospace", inconsolata);
+        text.add_fallback(Font::new(Blob::new(Arc::new(NOTO_EMOJI_COLR_SUBSET)), 0));
+        text.add_fallback(Font::new(Blob::new(Arc::new(NOTO_EMOJI_CBTF_SUBSET)), 0));
+        text
     }
 
-    /// Add a text run which supports some emoji.
-    ///
-    /// The supported Emoji are ✅, 👀, 🎉, and 🤠.
-    /// This subset is chosen to demonstrate the emoji support, whilst
-    /// not significantly increasing repository size.
-    ///
-    /// Note that Vello does support COLR emoji, but does not currently support
-    /// any other forms of emoji.
-    #[allow(clippy::too_many_arguments)]
-    pub fn add_colr_emoji_run<'a>(
-        &mut self,
-        scene: &mut Scene,
-        size: f32,
-        transform: Affine,
-        glyph_transform: Option<Affine>,
-        style: impl Into<StyleRef<'a>>,
-        text: &str,
-    ) {
-        let font = self.noto_emoji_colr_subset.clone();
-        self.add_var_run(
-            scene,
-            Some(&font),
-            size,
-            &[],
-            // This should be unused
-            &Brush::Solid(Color::WHITE),
-            transform,
-            glyph_transform,
-            style,
-            text,
-        );
+    /// Replaces the font of the family
+    pub fn add_family(&mut self, family: impl Into<String>, font: Font) {
+        self.families.insert(family.into(), LoadedFont::load(font));
     }
 
-    /// Add a text run which supports some emoji.
-    ///
-    /// The supported Emoji are ✅, 👀, 🎉, and 🤠.
-    /// This subset is chosen to demonstrate the emoji support, whilst
-    /// not significantly increasing repository size.
-    ///
-    /// This will use a CBTF font, which Vello supports.
-    #[allow(clippy::too_many_arguments)]
-    pub fn add_bitmap_emoji_run<'a>(
-        &mut self,
-        scene: &mut Scene,
-        size: f32,
-        transform: Affine,
-        glyph_transform: Option<Affine>,
-        style: impl Into<StyleRef<'a>>,
-        text: &str,
-    ) {
-        let font = self.noto_emoji_cbtf_subset.clone();
-        self.add_var_run(
-            scene,
-            Some(&font),
-            size,
-            &[],
-            // This should be unused
-            &Brush::Solid(Color::WHITE),
-            transform,
-            glyph_transform,
-            style,
-            text,
-        );
+    pub fn add_fallback(&mut self, font: Font) {
+        self.fallbacks.push(LoadedFont::load(font));
     }
 
-    #[allow(clippy::too_many_arguments)]
-    pub fn add_run<'a>(
-        &mut self,
-        scene: &mut Scene,
-        font: Option<&Font>,
-        size: f32,
-        brush: impl Into<BrushRef<'a>>,
-        transform: Affine,
+    /// Roboto is used for unknown families
+    pub fn get_font(&self, family: &str) -> &Font {
+        self.get_loaded_font(family).borrow_owner()
+    }
+
+    fn get_loaded_font(&self, family: &str) -> &LoadedFont {
+        self.families
+            .get(family)
+            .or_else(|| self.families.get(DEFAULT_FAMILY))
+            .unwrap()
+    }
+
+    pub fn make_font_run(
+        &self,
+        styles: &TextStyles,
         glyph_transform: Option<Affine>,
-        style: impl Into<StyleRef<'a>>,
-        text: &str,
-    ) {
-        self.add_var_run(
-            scene,
-            font,
-            size,
-            &[],
-            brush,
-            transform,
+    ) -> TextRunner<'_> {
+        TextRunner::new(
+            self.get_loaded_font(&styles.font_family),
+            &self.fallbacks,
+            styles.size as f32,
             glyph_transform,
-            style,
-            text,
-        );
+        )
     }
+}
 
-    #[allow(clippy::too_many_arguments)]
-    pub fn add_var_run<'a>(
-        &mut self,
-        scene: &mut Scene,
-        font: Option<&Font>,
-        size: f32,
-        variations: &[(&str, f32)],
-        brush: impl Into<BrushRef<'a>>,
-        transform: Affine,
-        glyph_transform: Option<Affine>,
-        style: impl Into<StyleRef<'a>>,
-        text: &str,
-    ) {
-        let default_font = if variations.is_empty() {
-            &self.roboto
-        } else {
-            &self.inconsolata
-        };
-        let font = font.unwrap_or(default_font);
-        let font_ref = to_font_ref(font).unwrap();
-        let brush = brush.into();
-        let style = style.into();
-        let axes = font_ref.axes();
-        let font_size = skrifa::instance::Size::new(size);
-        let var_loc = axes.location(variations.iter().copied());
-        let charmap = font_ref.charmap();
-        let metrics = font_ref.metrics(font_size, &var_loc);
-        let line_height = metrics.ascent - metrics.descent + metrics.leading;
-        let glyph_metrics = font_ref.glyph_metrics(font_size, &var_loc);
-        let mut pen_x = 0f32;
-        let mut pen_y = 0f32;
-        scene
-            .draw_glyphs(font)
-            .font_size(size)
-            .transform(transform)
-            .glyph_transform(glyph_transform)
-            .normalized_coords(var_loc.coords())
-            .brush(brush)
-            .hint(false)
-            .draw(
-                style,
-                text.chars().filter_map(|ch| {
-                    if ch == '\n' {
-                        pen_y += line_height;
-                        pen_x = 0.0;
-                        return None;
-                    }
-                    let gid = charmap.map(ch).unwrap_or_default();
-                    let advance = glyph_metrics.advance_width(gid).unwrap_or_default();
-                    let x = pen_x;
-                    pen_x += advance;
-                    Some(Glyph {
-                        id: gid.to_u32(),
-                        x,
-                        y: pen_y,
-                    })
-                }),
-            );
+impl Default for SimpleText {
+    fn default() -> Self {
+        Self::new()
     }
+}
 
-    pub fn make_font_run(&self, size: f32, glyph_transform: Option<Affine>) -> TextRunner {
-        TextRunner::new(&self.inconsolata, size, &[], glyph_transform)
+impl LoadedFont {
+    fn load(font: Font) -> Self {
+        Self::new(font, |font| {
+            rustybuzz::Face::from_slice(font.data.as_ref(), font.index)
+        })
     }
+}
 
-    pub fn add(
-        &mut self,
-        scene: &mut Scene,
-        font: Option<&Font>,
-        size: f32,
-        brush: Option<&Brush>,
-        transform: Affine,
-        text: &str,
-    ) {
-        let brush = brush.unwrap_or(&Brush::Solid(Color::WHITE));
-        self.add_run(
-            scene,
-            font,
-            size,
-            brush,
-            transform,
-            None,
-            Fill::NonZero,
-            text,
-        );
+/// Characters that are not drawn on their own: variation selectors and joiners
+fn is_invisible(ch: char) -> bool {
+    matches!(ch, '\u{200b}'..='\u{200d}' | '\u{fe00}'..='\u{fe0f}' | '\u{e0100}'..='\u{e01ef}')
+}
+
+/// Common scripts are left for the shaper to guess
+fn to_shaper_script(script: Script) -> Option<rustybuzz::Script> {
+    if matches!(script, Script::Common | Script::Inherited | Script::Unknown) {
+        return None;
     }
+    let tag = script.short_name().as_bytes().try_into().ok()?;
+    rustybuzz::Script::from_iso15924_tag(Tag::from_bytes(tag))
 }
 
 fn to_font_ref(font: &Font) -> Option<FontRef<'_>> {
@@ -323,4 +575,219 @@ fn to_font_ref(font: &Font) -> Option<FontRef<'_>> {
         FileRef::Collection(collection) => collection.get(font.index).ok(),
     }
 }
-*/
+
+#[cfg(test)]
+mod tests {
+    use kreuz_ui::SceneCommand;
+    use peniko::Color;
+
+    use super::*;
+
+    fn runner(text: &SimpleText) -> TextRunner<'_> {
+        let styles = TextStyles {
+            color: Color::BLACK,
+            font_family: DEFAULT_FAMILY.into(),
+            size: 16.0,
+        };
+        text.make_font_run(&styles, None)
+    }
+
+    fn ranges(layout: &TextLayout) -> Vec<Range<usize>> {
+        layout.lines.iter().map(|line| line.range.clone()).collect()
+    }
+
+    fn line(range: Range<usize>, width: f32, indent: f32) -> TextLine {
+        TextLine {
+            range,
+            width,
+            indent,
+            hard_break: false,
+            rtl: false,
+        }
+    }
+
+    #[test]
+    fn wrap_at_mandatory_breaks() {
+        let text = SimpleText::new();
+        let runner = runner(&text);
+        let layout = runner.wrap("ab\ncd", WritingDirection::Natural, f32::INFINITY, 5.0);
+        assert_eq!(ranges(&layout), [0..2, 3..5]);
+        assert!(layout.lines.iter().all(|line| line.hard_break));
+        assert!(layout.lines.iter().all(|line| line.indent == 5.0));
+    }
+
+    #[test]
+    fn wrap_long_word_on_its_own_line() {
+        let text = SimpleText::new();
+        let runner = runner(&text);
+        let max_width = runner.get_word_width("a b") + 1.0;
+        let layout = runner.wrap(
+            "a b unbreakable c",
+            WritingDirection::Natural,
+            max_width,
+            0.0,
+        );
+        assert_eq!(ranges(&layout), [0..3, 4..15, 16..17]);
+        assert!(layout.lines[1].width > max_width);
+        assert!(!layout.lines[0].hard_break);
+        assert!(layout.lines[2].hard_break);
+    }
+
+    #[test]
+    fn wrap_excludes_trailing_spaces() {
+        let text = SimpleText::new();
+        let runner = runner(&text);
+        let max_width = runner.get_word_width("ab") + 1.0;
+        let layout = runner.wrap("ab   cd  ", WritingDirection::Natural, max_width, 0.0);
+        assert_eq!(ranges(&layout), [0..2, 5..7]);
+        assert_eq!(layout.lines[0].width, runner.get_word_width("ab"));
+        assert_eq!(layout.lines[1].width, runner.get_word_width("cd"));
+    }
+
+    #[test]
+    fn justify_spreads_free_space_over_spaces() {
+        let text = "a b c";
+        let line = line(0..5, 30.0, 0.0);
+        assert_eq!(line.align(text, TextAlign::Justify, 50.0), (0.0, 10.0));
+
+        // Last lines of paragraphs keep to their side
+        let last = TextLine {
+            hard_break: true,
+            ..line
+        };
+        assert_eq!(last.align(text, TextAlign::Justify, 50.0), (0.0, 0.0));
+        let rtl = TextLine { rtl: true, ..last };
+        assert_eq!(rtl.align(text, TextAlign::Justify, 50.0), (20.0, 0.0));
+    }
+
+    #[test]
+    fn right_and_center_offsets() {
+        let text = "abc";
+        let ltr = line(0..3, 30.0, 10.0);
+        assert_eq!(ltr.align(text, TextAlign::Left, 100.0), (10.0, 0.0));
+        assert_eq!(ltr.align(text, TextAlign::Right, 100.0), (70.0, 0.0));
+        assert_eq!(ltr.align(text, TextAlign::Center, 100.0), (40.0, 0.0));
+
+        // Indentation of RTL lines is on the right
+        let rtl = TextLine { rtl: true, ..ltr };
+        assert_eq!(rtl.align(text, TextAlign::Left, 100.0), (0.0, 0.0));
+        assert_eq!(rtl.align(text, TextAlign::Right, 100.0), (60.0, 0.0));
+        assert_eq!(rtl.align(text, TextAlign::Center, 100.0), (30.0, 0.0));
+
+        // Lines wider than the box are not shifted
+        let wide = line(0..3, 130.0, 0.0);
+        assert_eq!(wide.align(text, TextAlign::Right, 100.0), (0.0, 0.0));
+    }
+
+    fn visual_clusters(runner: &TextRunner, layout: &TextLayout) -> Vec<usize> {
+        runner
+            .shape_line(layout, &layout.lines[0])
+            .iter()
+            .map(|(_, glyph)| glyph.cluster)
+            .collect()
+    }
+
+    #[test]
+    fn mixed_direction_runs_in_visual_order() {
+        let text = SimpleText::new();
+        let runner = runner(&text);
+
+        // Hebrew letters are two bytes long
+        let ltr = runner.wrap(
+            "ab \u{5d0}\u{5d1} cd",
+            WritingDirection::Natural,
+            f32::INFINITY,
+            0.0,
+        );
+        assert!(!ltr.lines[0].rtl);
+        assert_eq!(visual_clusters(&runner, &ltr), [0, 1, 2, 5, 3, 7, 8, 9]);
+
+        let rtl = runner.wrap(
+            "\u{5d0}\u{5d1} cd",
+            WritingDirection::Natural,
+            f32::INFINITY,
+            0.0,
+        );
+        assert!(rtl.lines[0].rtl);
+        assert_eq!(visual_clusters(&runner, &rtl), [5, 6, 4, 2, 0]);
+    }
+
+    #[test]
+    fn itemize_latin_arabic_and_emoji() {
+        let text = SimpleText::new();
+        let runner = runner(&text);
+        let line = "ab \u{639}\u{631} \u{1f389}";
+        let items = runner
+            .itemize(line, 0..line.len())
+            .into_iter()
+            .map(|item| (item.range, item.face, item.script))
+            .collect::<Vec<_>>();
+        // Spaces stay with the preceding item, Arabic has no bundled font and gets `.notdef`
+        assert_eq!(
+            items,
+            [
+                (0..3, 0, Script::Latin),
+                (3..8, 0, Script::Arabic),
+                (8..12, 1, Script::Common),
+            ]
+        );
+    }
+
+    #[test]
+    fn line_width_matches_drawn_width() {
+        let text = SimpleText::new();
+        let runner = runner(&text);
+        let mut scene = Scene::new();
+        let layout = runner.wrap("AV. AWAY Ta", WritingDirection::Natural, f32::INFINITY, 0.0);
+        let line = &layout.lines[0];
+        let drawn = runner.draw_line(
+            &mut scene,
+            &Brush::default(),
+            &Style::Fill(peniko::Fill::NonZero),
+            Affine::IDENTITY,
+            &layout,
+            line,
+            0.0,
+        );
+        assert_eq!(line.width, drawn);
+    }
+    /// Bundled fonts of the glyph runs drawn for `word`, in order
+    fn drawn_fonts(text: &SimpleText, word: &str) -> Vec<&'static str> {
+        let mut scene = Scene::new();
+        runner(text).draw_word(
+            &mut scene,
+            &Brush::default(),
+            &Style::Fill(peniko::Fill::NonZero),
+            Affine::IDENTITY,
+            word,
+        );
+        let bundled = [
+            ("Roboto", ROBOTO_FONT),
+            ("COLR", NOTO_EMOJI_COLR_SUBSET),
+            ("CBDT", NOTO_EMOJI_CBTF_SUBSET),
+        ];
+        scene
+            .commands()
+            .iter()
+            .filter_map(|command| match command {
+                SceneCommand::Glyphs(run) => Some(
+                    bundled
+                        .iter()
+                        .find(|(_, data)| run.font.data.data() == *data)
+                        .map_or("unknown", |(name, _)| name),
+                ),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn emoji_fall_back_to_colr_then_cbdt() {
+        let mut text = SimpleText::new();
+        assert_eq!(drawn_fonts(&text, "ab \u{1f389}"), ["Roboto", "COLR"]);
+
+        // Without the COLR font the bitmap one is next
+        text.fallbacks.remove(0);
+        assert_eq!(drawn_fonts(&text, "ab \u{1f389}"), ["Roboto", "CBDT"]);
+    }
+}
@@ -2,4 +2,4 @@ use super::*;
 
 pub trait View {
     fn build(self, arena: &mut Arena) -> ViewId;
-}
\ No newline at end of file
+}
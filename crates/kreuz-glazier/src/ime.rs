@@ -0,0 +1,115 @@
+use glazier::{
+    text::{Action, Affinity, HitTestPoint, InputHandler, Selection},
+    TextFieldToken,
+};
+use kreuz_window::ImeEvent;
+use peniko::kurbo::{Point, Rect};
+use std::{
+    borrow::Cow,
+    mem,
+    ops::Range,
+    sync::{Arc, Mutex},
+};
+
+/// Text that the platform is composing, glazier edits it through `ImeInput` and the changes are
+/// reported to the app as `ImeEvent`s once the input lock is released
+#[derive(Default)]
+pub(crate) struct ImeState {
+    pub token: Option<TextFieldToken>,
+    /// Area reported by the app, in logical pixels
+    pub cursor_area: Rect,
+    text: String,
+    selection: Selection,
+    composition: Option<Range<usize>>,
+    composing: bool,
+}
+
+impl ImeState {
+    /// Turns the edits made since the last call into events
+    pub fn take_events(&mut self) -> Vec<ImeEvent> {
+        let mut events = Vec::new();
+        if let Some(range) = self.composition.clone() {
+            let cursor = self.selection.range();
+            let cursor = (
+                cursor.start.saturating_sub(range.start),
+                cursor.end.saturating_sub(range.start),
+            );
+            events.push(ImeEvent::Preedit {
+                text: self.text[range].into(),
+                cursor: Some(cursor),
+            });
+            self.composing = true;
+            return events;
+        }
+        if mem::take(&mut self.composing) {
+            events.push(ImeEvent::Preedit {
+                text: String::new(),
+                cursor: None,
+            });
+        }
+        if !self.text.is_empty() {
+            events.push(ImeEvent::Commit {
+                text: mem::take(&mut self.text),
+            });
+            self.selection = Selection::caret(0);
+        }
+        events
+    }
+}
+
+pub(crate) struct ImeInput(pub Arc<Mutex<ImeState>>);
+
+impl InputHandler for ImeInput {
+    fn selection(&self) -> Selection {
+        self.0.lock().unwrap().selection
+    }
+
+    fn set_selection(&mut self, selection: Selection) {
+        self.0.lock().unwrap().selection = selection;
+    }
+
+    fn composition_range(&self) -> Option<Range<usize>> {
+        self.0.lock().unwrap().composition.clone()
+    }
+
+    fn set_composition_range(&mut self, range: Option<Range<usize>>) {
+        self.0.lock().unwrap().composition = range;
+    }
+
+    fn is_char_boundary(&self, i: usize) -> bool {
+        self.0.lock().unwrap().text.is_char_boundary(i)
+    }
+
+    fn len(&self) -> usize {
+        self.0.lock().unwrap().text.len()
+    }
+
+    fn slice(&self, range: Range<usize>) -> Cow<str> {
+        self.0.lock().unwrap().text[range].to_string().into()
+    }
+
+    fn replace_range(&mut self, range: Range<usize>, text: &str) {
+        let mut state = self.0.lock().unwrap();
+        state.text.replace_range(range.clone(), text);
+        state.selection = Selection::caret(range.start + text.len());
+    }
+
+    fn hit_test_point(&self, _point: Point) -> HitTestPoint {
+        HitTestPoint::new(self.len(), false)
+    }
+
+    fn line_range(&self, _index: usize, _affinity: Affinity) -> Range<usize> {
+        0..self.len()
+    }
+
+    fn bounding_box(&self) -> Option<Rect> {
+        Some(self.0.lock().unwrap().cursor_area)
+    }
+
+    fn slice_bounding_box(&self, _range: Range<usize>) -> Option<Rect> {
+        self.bounding_box()
+    }
+
+    // Editing commands come as key events, the app handles them there
+    fn handle_action(&mut self, _action: Action) {}
+}
@@ -0,0 +1,95 @@
+use glazier::{Code, KbKey, KeyEvent as GlazierKeyEvent, Location, Modifiers as GlazierModifiers};
+use kreuz_window::{Key, KeyCode, KeyEvent, KeyLocation, Modifiers, NamedKey};
+
+macro_rules! map_variants {
+    ($value:expr, $from:ident => $to:ident, [$($variant:ident),* $(,)?]) => {
+        match $value {
+            $($from::$variant => Some($to::$variant),)*
+            _ => None,
+        }
+    };
+}
+
+/// Glazier doesn't report text of key presses, so it is taken from the key itself. When IME is
+/// allowed, text is delivered through `ImeEvent::Commit` instead
+pub(crate) fn convert_key_event(event: &GlazierKeyEvent, ime_allowed: bool) -> KeyEvent {
+    let text = match &event.key {
+        KbKey::Character(text) if !ime_allowed => Some(text.clone()),
+        _ => None,
+    };
+    KeyEvent {
+        key: convert_key(&event.key),
+        code: convert_key_code(event.code).unwrap_or(KeyCode::Unidentified),
+        location: convert_key_location(event.location),
+        repeat: event.repeat,
+        text,
+    }
+}
+
+pub(crate) fn convert_modifiers(mods: GlazierModifiers) -> Modifiers {
+    Modifiers {
+        shift: mods.shift(),
+        control: mods.ctrl(),
+        alt: mods.alt(),
+        super_key: mods.meta(),
+    }
+}
+
+fn convert_key(key: &KbKey) -> Key {
+    match key {
+        // Winit reports space as a named key, keep it the same
+        KbKey::Character(ch) if ch == " " => Key::Named(NamedKey::Space),
+        KbKey::Character(ch) => Key::Character(ch.clone()),
+        KbKey::Dead => Key::Dead(None),
+        KbKey::Meta => Key::Named(NamedKey::Super),
+        key => convert_named_key(key).map_or(Key::Unidentified, Key::Named),
+    }
+}
+
+fn convert_key_location(location: Location) -> KeyLocation {
+    match location {
+        Location::Left => KeyLocation::Left,
+        Location::Right => KeyLocation::Right,
+        Location::Numpad => KeyLocation::Numpad,
+        _ => KeyLocation::Standard,
+    }
+}
+
+fn convert_named_key(key: &KbKey) -> Option<NamedKey> {
+    map_variants!(key, KbKey => NamedKey, [
+        Alt, AltGraph, CapsLock, Control, Fn, NumLock, ScrollLock, Shift, Super,
+        Enter, Tab,
+        ArrowDown, ArrowLeft, ArrowRight, ArrowUp, End, Home, PageDown, PageUp,
+        Backspace, Clear, Copy, Cut, Delete, Insert, Paste, Redo, Undo,
+        ContextMenu, Escape, Find, Help, Pause, PrintScreen,
+        MediaPlayPause, MediaStop, MediaTrackNext, MediaTrackPrevious,
+        AudioVolumeDown, AudioVolumeUp, AudioVolumeMute,
+        F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+        F13, F14, F15, F16, F17, F18, F19, F20, F21, F22, F23, F24,
+    ])
+}
+
+fn convert_key_code(code: Code) -> Option<KeyCode> {
+    match code {
+        Code::MetaLeft => return Some(KeyCode::SuperLeft),
+        Code::MetaRight => return Some(KeyCode::SuperRight),
+        _ => {}
+    }
+    map_variants!(code, Code => KeyCode, [
+        Backquote, Backslash, BracketLeft, BracketRight, Comma,
+        Digit0, Digit1, Digit2, Digit3, Digit4, Digit5, Digit6, Digit7, Digit8, Digit9,
+        Equal, IntlBackslash,
+        KeyA, KeyB, KeyC, KeyD, KeyE, KeyF, KeyG, KeyH, KeyI, KeyJ, KeyK, KeyL, KeyM,
+        KeyN, KeyO, KeyP, KeyQ, KeyR, KeyS, KeyT, KeyU, KeyV, KeyW, KeyX, KeyY, KeyZ,
+        Minus, Period, Quote, Semicolon, Slash,
+        AltLeft, AltRight, Backspace, CapsLock, ContextMenu, ControlLeft, ControlRight, Enter,
+        ShiftLeft, ShiftRight, Space, Tab,
+        Delete, End, Home, Insert, PageDown, PageUp, ArrowDown, ArrowLeft, ArrowRight, ArrowUp,
+        NumLock, Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7,
+        Numpad8, Numpad9, NumpadAdd, NumpadDecimal, NumpadDivide, NumpadEnter, NumpadMultiply,
+        NumpadSubtract,
+        Escape, PrintScreen, ScrollLock, Pause,
+        F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+        F13, F14, F15, F16, F17, F18, F19, F20, F21, F22, F23, F24,
+    ])
+}
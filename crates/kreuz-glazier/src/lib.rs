@@ -0,0 +1,463 @@
+mod ime;
+mod keyboard;
+mod subwindow;
+mod window;
+
+pub use subwindow::*;
+pub use window::*;
+
+use anyhow::{anyhow, Result};
+use glazier::{
+    text::InputHandler, Application, KeyEvent as GlazierKeyEvent, Modifiers as GlazierModifiers,
+    PointerButton, PointerEvent, PointerId, PointerType, Region, Scale, TextFieldToken, WinHandler,
+    WindowBuilder, WindowHandle, WindowLevel as GlazierWindowLevel, WindowState,
+};
+use kreuz_window::{
+    AppHandler, AppResponce, ButtonState, MouseButton, ScrollDelta, SubwindowParams, TouchPhase,
+    WindowEvent, WindowId, WindowParams,
+};
+use peniko::kurbo::Size;
+use std::{
+    any::Any,
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use ime::{ImeInput, ImeState};
+use window::supported_params;
+
+struct GlazierState<A> {
+    /// Taken out while the app handles something, so that glazier can call handlers of other
+    /// windows from inside the app, like it does when a window is invalidated or resized
+    app: Option<A>,
+    windows: HashMap<WindowId, WindowHandle>,
+    /// Subwindows with ids of their parents
+    subwindows: HashMap<WindowId, (WindowId, WindowHandle)>,
+    /// Events that came while the app was taken out, in order of arrival
+    pending: VecDeque<(WindowId, WindowEvent)>,
+    /// First error reported by the app, the application quits after it
+    error: Option<anyhow::Error>,
+}
+
+/// Shared by handlers of all windows. The state is unlocked before calling the app and acting on
+/// its responces, because glazier may call handlers synchronously while creating or closing
+/// windows
+struct GlazierEventLoop<A> {
+    state: Arc<Mutex<GlazierState<A>>>,
+}
+
+impl<A> Clone for GlazierEventLoop<A> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<A: AppHandler<GlazierWinHandler, GlazierSubwinHandler> + Send + 'static> GlazierEventLoop<A> {
+    fn handle_event(&self, id: WindowId, event: WindowEvent) {
+        self.state.lock().unwrap().pending.push_back((id, event));
+        self.flush_pending();
+    }
+
+    /// Delivers queued events unless the app is busy further up the stack, it will deliver them
+    /// after returning
+    fn flush_pending(&self) {
+        loop {
+            let mut state = self.state.lock().unwrap();
+            if state.app.is_none() {
+                return;
+            }
+            let Some((id, event)) = state.pending.pop_front() else {
+                return;
+            };
+            let mut app = state.app.take().unwrap();
+            drop(state);
+            let responce = app.handle_window_event(id, event);
+            self.return_app(app);
+            self.handle_responce(responce);
+        }
+    }
+
+    fn return_app(&self, app: A) {
+        self.state.lock().unwrap().app = Some(app);
+    }
+
+    fn handle_responce(&self, responce: AppResponce) {
+        match responce {
+            AppResponce::Handled => {}
+            AppResponce::Error(err) => self.exit_with_error(anyhow!(err)),
+            AppResponce::CloseWindow(id) => self.close_window(id),
+            AppResponce::CreateNewWindow(params) => self.create_window(params),
+            AppResponce::CreateNewSubwindow { parent, params } => {
+                self.create_subwindow(parent, params);
+            }
+        }
+    }
+
+    fn create_window(&self, params: WindowParams) {
+        let params = supported_params(params);
+        let window_state = match params.maximized {
+            true => WindowState::Maximized,
+            false => WindowState::Restored,
+        };
+        let mut builder = WindowBuilder::new(Application::global())
+            .size(params.size)
+            .resizable(params.resizable)
+            .show_titlebar(params.decorations)
+            .transparent(params.transparent)
+            .title(params.title.clone())
+            .window_state(window_state);
+        if let Some(position) = params.position {
+            builder = builder.position(position);
+        }
+        if let Some(size) = params.min_size {
+            builder = builder.min_size(size);
+        }
+        let visible = params.visible;
+        let handler = GlazierWindow::new(self.clone(), WindowKind::Window(params));
+        match builder.handler(Box::new(handler)).build() {
+            Ok(handle) if visible => handle.show(),
+            Ok(_) => {}
+            Err(err) => self.exit_with_error(err.into()),
+        }
+    }
+
+    fn create_subwindow(&self, parent: WindowId, params: SubwindowParams) {
+        let parent_handle = self.state.lock().unwrap().windows.get(&parent).cloned();
+        let Some(parent_handle) = parent_handle else {
+            let err = anyhow!("can't create a subwindow of unknown window {parent:?}");
+            return self.exit_with_error(err);
+        };
+        // Glazier positions drop downs relative to the parent
+        let handler = GlazierWindow::new(self.clone(), WindowKind::Subwindow { parent });
+        let builder = WindowBuilder::new(Application::global())
+            .handler(Box::new(handler))
+            .size(params.size)
+            .position(params.position)
+            .resizable(false)
+            .show_titlebar(false)
+            .level(GlazierWindowLevel::DropDown(parent_handle));
+        match builder.build() {
+            Ok(handle) => handle.show(),
+            Err(err) => self.exit_with_error(err.into()),
+        }
+    }
+
+    /// Called from `WinHandler::connect`, the window can't be used before it
+    fn connect_window(
+        &self,
+        id: WindowId,
+        kind: &WindowKind,
+        handle: &WindowHandle,
+        ime: Arc<Mutex<ImeState>>,
+    ) {
+        let mut state = self.state.lock().unwrap();
+        match kind {
+            WindowKind::Window(_) => {
+                state.windows.insert(id, handle.clone());
+            }
+            WindowKind::Subwindow { parent } => {
+                state.subwindows.insert(id, (*parent, handle.clone()));
+            }
+        }
+        // Apps never create windows while handling something, glazier only connects them when
+        // acting on responces
+        let Some(mut app) = state.app.take() else {
+            drop(state);
+            return self.exit_with_error(anyhow!("window {id:?} connected while the app was busy"));
+        };
+        drop(state);
+
+        let responce = match kind {
+            WindowKind::Window(params) => {
+                let window = GlazierWinHandler::new(handle.clone(), params.clone(), ime);
+                app.handle_window_update(id, window)
+            }
+            WindowKind::Subwindow { .. } => {
+                let window = GlazierSubwinHandler {
+                    handle: handle.clone(),
+                };
+                app.handle_subwindow_update(id, window)
+            }
+        };
+        self.return_app(app);
+        self.handle_responce(responce);
+        self.flush_pending();
+    }
+
+    /// Closes the window together with its subwindows, also called when glazier destroys one
+    fn close_window(&self, id: WindowId) {
+        let mut state = self.state.lock().unwrap();
+        let mut closed = Vec::new();
+        let is_window = match state.windows.remove(&id) {
+            Some(handle) => {
+                closed.push(handle);
+                true
+            }
+            None => false,
+        };
+        if let Some((_, handle)) = state.subwindows.remove(&id) {
+            closed.push(handle);
+        }
+        state.subwindows.retain(|_, (parent, handle)| {
+            if *parent == id {
+                closed.push(handle.clone());
+            }
+            *parent != id
+        });
+        let quit = is_window && state.windows.is_empty();
+        drop(state);
+
+        for handle in closed {
+            handle.close();
+        }
+        if quit {
+            Application::global().quit();
+        }
+    }
+
+    fn exit_with_error(&self, err: anyhow::Error) {
+        let mut state = self.state.lock().unwrap();
+        if state.error.is_none() {
+            state.error = Some(err);
+        }
+        drop(state);
+        Application::global().quit();
+    }
+}
+
+enum WindowKind {
+    Window(WindowParams),
+    Subwindow { parent: WindowId },
+}
+
+/// Receives glazier callbacks of a single window and turns them into `WindowEvent`s
+struct GlazierWindow<A> {
+    event_loop: GlazierEventLoop<A>,
+    id: WindowId,
+    kind: WindowKind,
+    cursor_inside: bool,
+    modifiers: GlazierModifiers,
+    /// Glazier pointer ids can't be converted to numbers, so touches get their own
+    touches: HashMap<PointerId, u64>,
+    next_touch_id: u64,
+    ime: Arc<Mutex<ImeState>>,
+}
+
+impl<A: AppHandler<GlazierWinHandler, GlazierSubwinHandler> + Send + 'static> GlazierWindow<A> {
+    fn new(event_loop: GlazierEventLoop<A>, kind: WindowKind) -> Self {
+        Self {
+            event_loop,
+            id: WindowId::new(),
+            kind,
+            cursor_inside: false,
+            modifiers: Default::default(),
+            touches: HashMap::new(),
+            next_touch_id: 0,
+            ime: Default::default(),
+        }
+    }
+
+    fn pass_event(&self, event: WindowEvent) {
+        self.event_loop.handle_event(self.id, event);
+    }
+
+    fn update_modifiers(&mut self, modifiers: GlazierModifiers) {
+        if self.modifiers != modifiers {
+            self.modifiers = modifiers;
+            let modifiers = keyboard::convert_modifiers(modifiers);
+            self.pass_event(WindowEvent::ModifiersChanged { modifiers });
+        }
+    }
+
+    fn pass_key_event(&mut self, event: &GlazierKeyEvent, pressed: bool) {
+        self.update_modifiers(event.mods);
+        let ime_allowed = self.ime.lock().unwrap().token.is_some();
+        let event = keyboard::convert_key_event(event, ime_allowed);
+        self.pass_event(match pressed {
+            true => WindowEvent::KeyDown { event },
+            false => WindowEvent::KeyUp { event },
+        });
+    }
+
+    /// Returns `false` if the event doesn't come from a touch screen
+    fn pass_touch(&mut self, event: &PointerEvent, phase: TouchPhase) -> bool {
+        let PointerType::Touch(info) = &event.pointer_type else {
+            return false;
+        };
+        let id = *self.touches.entry(event.pointer_id).or_insert_with(|| {
+            self.next_touch_id += 1;
+            self.next_touch_id
+        });
+        if let TouchPhase::Ended | TouchPhase::Cancelled = phase {
+            self.touches.remove(&event.pointer_id);
+        }
+        self.pass_event(WindowEvent::Touch {
+            id,
+            pos: event.pos,
+            phase,
+            force: Some(info.pressure as f64),
+        });
+        true
+    }
+
+    fn pass_mouse_button(&mut self, event: &PointerEvent, state: ButtonState) {
+        self.update_modifiers(event.modifiers);
+        let button = match event.button {
+            PointerButton::Primary => MouseButton::Left,
+            PointerButton::Secondary => MouseButton::Right,
+            PointerButton::Auxiliary => MouseButton::Middle,
+            PointerButton::X1 => MouseButton::Back,
+            PointerButton::X2 => MouseButton::Forward,
+            PointerButton::None => return,
+        };
+        self.pass_event(WindowEvent::MouseButton { button, state });
+    }
+}
+
+impl<A: AppHandler<GlazierWinHandler, GlazierSubwinHandler> + Send + 'static> WinHandler
+    for GlazierWindow<A>
+{
+    fn connect(&mut self, handle: &WindowHandle) {
+        self.event_loop
+            .connect_window(self.id, &self.kind, handle, self.ime.clone());
+    }
+
+    fn size(&mut self, size: Size) {
+        // Glazier sizes are in display points, which are logical pixels
+        self.pass_event(WindowEvent::Resize { new_size: size });
+    }
+
+    fn scale(&mut self, scale: Scale) {
+        self.pass_event(WindowEvent::ScaleFactorChanged { scale: scale.x() });
+    }
+
+    fn prepare_paint(&mut self) {}
+
+    fn paint(&mut self, _invalid: &Region) {
+        self.pass_event(WindowEvent::Redraw);
+    }
+
+    fn key_down(&mut self, event: &GlazierKeyEvent) -> bool {
+        self.pass_key_event(event, true);
+        // Unhandled keys are sent to the focused text field, which reports them as IME events
+        let ime_allowed = self.ime.lock().unwrap().token.is_some();
+        !(ime_allowed && matches!(event.key, glazier::KbKey::Character(_)))
+    }
+
+    fn key_up(&mut self, event: &GlazierKeyEvent) {
+        self.pass_key_event(event, false);
+    }
+
+    fn acquire_input_lock(
+        &mut self,
+        _token: TextFieldToken,
+        _mutable: bool,
+    ) -> Box<dyn InputHandler> {
+        Box::new(ImeInput(self.ime.clone()))
+    }
+
+    fn release_input_lock(&mut self, _token: TextFieldToken) {
+        let events = self.ime.lock().unwrap().take_events();
+        for event in events {
+            self.pass_event(WindowEvent::Ime { event });
+        }
+    }
+
+    fn zoom(&mut self, delta: f64) {
+        self.pass_event(WindowEvent::PinchGesture {
+            delta,
+            phase: TouchPhase::Moved,
+        });
+    }
+
+    fn wheel(&mut self, event: &PointerEvent) {
+        self.update_modifiers(event.modifiers);
+        let PointerType::Mouse(info) = &event.pointer_type else {
+            return;
+        };
+        // Glazier reports how much to scroll by, winit reports how much to move the content
+        self.pass_event(WindowEvent::MouseWheel {
+            delta: ScrollDelta::Pixels(-info.wheel_delta),
+            phase: TouchPhase::Moved,
+        });
+    }
+
+    fn pointer_move(&mut self, event: &PointerEvent) {
+        if self.pass_touch(event, TouchPhase::Moved) {
+            return;
+        }
+        self.update_modifiers(event.modifiers);
+        // Glazier has no enter callback
+        if !self.cursor_inside {
+            self.cursor_inside = true;
+            self.pass_event(WindowEvent::CursorEntered);
+        }
+        self.pass_event(WindowEvent::CursorMove { pos: event.pos });
+    }
+
+    fn pointer_down(&mut self, event: &PointerEvent) {
+        if !self.pass_touch(event, TouchPhase::Started) {
+            self.pass_mouse_button(event, ButtonState::Pressed);
+        }
+    }
+
+    fn pointer_up(&mut self, event: &PointerEvent) {
+        if !self.pass_touch(event, TouchPhase::Ended) {
+            self.pass_mouse_button(event, ButtonState::Released);
+        }
+    }
+
+    fn pointer_leave(&mut self) {
+        self.cursor_inside = false;
+        self.pass_event(WindowEvent::CursorLeft);
+    }
+
+    fn got_focus(&mut self) {
+        self.pass_event(WindowEvent::Focused { focused: true });
+    }
+
+    fn lost_focus(&mut self) {
+        self.pass_event(WindowEvent::Focused { focused: false });
+    }
+
+    // App can veto closing by not responding with `CloseWindow`
+    fn request_close(&mut self) {
+        self.pass_event(WindowEvent::CloseRequested);
+    }
+
+    fn destroy(&mut self) {
+        self.event_loop.close_window(self.id);
+    }
+
+    fn as_any(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+pub fn run_with_glazier<A: AppHandler<GlazierWinHandler, GlazierSubwinHandler> + Send + 'static>(
+    app: A,
+) -> Result<()> {
+    let application = Application::new()?;
+    let params = app.default_window_params();
+    let event_loop = GlazierEventLoop {
+        state: Arc::new(Mutex::new(GlazierState {
+            app: Some(app),
+            windows: HashMap::new(),
+            subwindows: HashMap::new(),
+            pending: VecDeque::new(),
+            error: None,
+        })),
+    };
+
+    event_loop.create_window(params);
+    if event_loop.state.lock().unwrap().error.is_none() {
+        application.run(None);
+    }
+    let error = event_loop.state.lock().unwrap().error.take();
+    match error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,40 @@
+use glazier::WindowHandle;
+use kreuz_window::{SubwindowHandler, SubwindowParams};
+use raw_window_handle::{
+    DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, WindowHandle as RawHandle,
+};
+
+use super::window::surface_size;
+
+#[derive(Clone)]
+pub struct GlazierSubwinHandler {
+    pub handle: WindowHandle,
+}
+
+impl HasWindowHandle for GlazierSubwinHandler {
+    fn window_handle(&self) -> Result<RawHandle<'_>, HandleError> {
+        self.handle.window_handle()
+    }
+}
+
+impl HasDisplayHandle for GlazierSubwinHandler {
+    fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        self.handle.display_handle()
+    }
+}
+
+impl SubwindowHandler for GlazierSubwinHandler {
+    fn request_redraw(&self) {
+        self.handle.invalidate();
+    }
+
+    fn get_params(&self) -> SubwindowParams {
+        let scale = self.handle.get_scale().unwrap_or_default();
+        SubwindowParams {
+            size: surface_size(&self.handle),
+            scale: scale.x(),
+            // Glazier keeps the position relative to the parent
+            position: self.handle.get_position(),
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use glazier::{WindowHandle, WindowState};
+use kreuz_window::{
+    Fullscreen, Icon, Theme, WindowButtons, WindowHandler, WindowLevel, WindowParams,
+};
+use peniko::kurbo::{Point, Rect, Size};
+use raw_window_handle::{
+    DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, WindowHandle as RawHandle,
+};
+use std::sync::{Arc, Mutex};
+
+use super::ime::ImeState;
+
+#[derive(Clone)]
+pub struct GlazierWinHandler {
+    /// Glazier handle, can be used for native menus and dialogs
+    pub handle: WindowHandle,
+    /// Parameters that glazier applied but can't report back, the ones it can't apply are never
+    /// stored, so that `get_params` shows the actual state
+    params: Arc<Mutex<WindowParams>>,
+    ime: Arc<Mutex<ImeState>>,
+}
+
+impl GlazierWinHandler {
+    pub(crate) fn new(
+        handle: WindowHandle,
+        params: WindowParams,
+        ime: Arc<Mutex<ImeState>>,
+    ) -> Self {
+        Self {
+            handle,
+            params: Arc::new(Mutex::new(params)),
+            ime,
+        }
+    }
+
+    fn update_params(&self, f: impl FnOnce(&mut WindowParams)) {
+        f(&mut self.params.lock().unwrap());
+    }
+}
+
+impl HasWindowHandle for GlazierWinHandler {
+    fn window_handle(&self) -> Result<RawHandle<'_>, HandleError> {
+        self.handle.window_handle()
+    }
+}
+
+impl HasDisplayHandle for GlazierWinHandler {
+    fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        self.handle.display_handle()
+    }
+}
+
+impl WindowHandler for GlazierWinHandler {
+    fn request_redraw(&self) {
+        self.handle.invalidate();
+    }
+
+    fn set_title(&self, title: &str) {
+        self.handle.set_title(title);
+        self.update_params(|params| params.title = title.into());
+    }
+
+    fn get_params(&self) -> WindowParams {
+        let cached = self.params.lock().unwrap().clone();
+        let scale = self.handle.get_scale().unwrap_or_default();
+        let position = self.handle.get_position();
+        let maximized = self.handle.get_window_state() == WindowState::Maximized;
+        WindowParams {
+            size: surface_size(&self.handle),
+            scale: scale.x(),
+            position: Some(position),
+            maximized,
+            ..cached
+        }
+    }
+
+    fn set_min_size(&self, _size: Option<Size>) {
+        // Glazier only supports it at creation
+        ignored("set_min_size");
+    }
+
+    fn set_max_size(&self, _size: Option<Size>) {
+        ignored("set_max_size");
+    }
+
+    fn set_resizable(&self, resizable: bool) {
+        self.handle.resizable(resizable);
+        self.update_params(|params| params.resizable = resizable);
+    }
+
+    fn set_enabled_buttons(&self, _buttons: WindowButtons) {
+        ignored("set_enabled_buttons");
+    }
+
+    fn set_maximized(&self, maximized: bool) {
+        let state = match maximized {
+            true => WindowState::Maximized,
+            false => WindowState::Restored,
+        };
+        self.handle.clone().set_window_state(state);
+    }
+
+    fn set_visible(&self, visible: bool) {
+        // Glazier windows can't be hidden once shown
+        if !visible {
+            return ignored("set_visible(false)");
+        }
+        self.handle.show();
+        self.update_params(|params| params.visible = visible);
+    }
+
+    fn set_transparent(&self, _transparent: bool) {
+        ignored("set_transparent");
+    }
+
+    fn set_blur(&self, _blur: bool) {
+        ignored("set_blur");
+    }
+
+    fn set_decorations(&self, decorations: bool) {
+        self.handle.show_titlebar(decorations);
+        self.update_params(|params| params.decorations = decorations);
+    }
+
+    fn set_window_icon(&self, _icon: Option<Icon>) {
+        ignored("set_window_icon");
+    }
+
+    fn set_theme(&self, _theme: Option<Theme>) {
+        ignored("set_theme");
+    }
+
+    fn set_resize_increments(&self, _increments: Option<Size>) {
+        ignored("set_resize_increments");
+    }
+
+    fn set_content_protected(&self, _protected: bool) {
+        ignored("set_content_protected");
+    }
+
+    fn set_window_level(&self, _level: WindowLevel) {
+        ignored("set_window_level");
+    }
+
+    fn set_fullscreen(&self, _fullscreen: Option<Fullscreen>) {
+        ignored("set_fullscreen");
+    }
+
+    fn set_ime_allowed(&self, allowed: bool) {
+        // Glazier delivers composed text to registered text fields only
+        let mut ime = self.ime.lock().unwrap();
+        match (allowed, ime.token) {
+            (true, None) => {
+                let token = self.handle.add_text_field();
+                ime.token = Some(token);
+                drop(ime);
+                self.handle.set_focused_text_field(Some(token));
+            }
+            (false, Some(token)) => {
+                ime.token = None;
+                drop(ime);
+                self.handle.remove_text_field(token);
+            }
+            _ => {}
+        }
+    }
+
+    fn set_ime_cursor_area(&self, position: Point, size: Size) {
+        let mut ime = self.ime.lock().unwrap();
+        ime.cursor_area = Rect::from_origin_size(position, size);
+        let token = ime.token;
+        drop(ime);
+        if let Some(token) = token {
+            self.handle
+                .update_text_field(token, glazier::text::Event::LayoutChanged);
+        }
+    }
+}
+
+/// Setters that glazier can't apply only warn, their value isn't stored
+fn ignored(setter: &str) {
+    log::warn!("glazier can't apply `{setter}`, the call is ignored");
+}
+
+/// Resets parameters that glazier can't apply at window creation to their defaults with a
+/// warning, so that `get_params` doesn't report them as applied
+pub(crate) fn supported_params(mut params: WindowParams) -> WindowParams {
+    let default = WindowParams::default();
+    macro_rules! unsupported {
+        ($($field:ident),* $(,)?) => {
+            $(
+                if params.$field != default.$field {
+                    log::warn!(
+                        "glazier doesn't support `{}` at window creation, it is ignored",
+                        stringify!($field),
+                    );
+                    params.$field = default.$field;
+                }
+            )*
+        };
+    }
+    unsupported!(
+        max_size,
+        enabled_buttons,
+        blur,
+        window_icon,
+        preferred_theme,
+        resize_increments,
+        content_protected,
+        window_level,
+        fullscreen,
+    );
+    params
+}
+
+/// Size of the window content in logical pixels
+pub(crate) fn surface_size(handle: &WindowHandle) -> Size {
+    let insets = handle.content_insets();
+    let size = handle.get_size();
+    Size::new(
+        size.width - insets.x_value(),
+        size.height - insets.y_value(),
+    )
+}
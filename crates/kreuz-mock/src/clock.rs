@@ -0,0 +1,19 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Fake time of a `MockBackend`, shared so that the app can take its time from it too
+#[derive(Clone, Debug, Default)]
+pub struct MockClock(Arc<Mutex<Duration>>);
+
+impl MockClock {
+    /// Time passed since the backend was created
+    pub fn now(&self) -> Duration {
+        *self.0.lock().unwrap()
+    }
+
+    pub(crate) fn set(&self, now: Duration) {
+        *self.0.lock().unwrap() = now;
+    }
+}
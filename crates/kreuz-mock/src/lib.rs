@@ -0,0 +1,184 @@
+mod clock;
+mod subwindow;
+mod view;
+mod window;
+
+pub use clock::*;
+pub use subwindow::*;
+pub use view::*;
+pub use window::*;
+
+use kreuz_window::{AppHandler, AppResponce, SubwindowParams, WindowEvent, WindowId, WindowParams};
+use std::{collections::BTreeMap, time::Duration};
+
+/// Drives an app without a display. Nothing happens on its own: the test creates windows, sends
+/// events and advances the clock, and every responce of the app is recorded after being acted on
+pub struct MockBackend<A: AppHandler<MockWindow, MockSubwindow>> {
+    app: A,
+    windows: BTreeMap<WindowId, MockWindow>,
+    subwindows: BTreeMap<WindowId, MockSubwindow>,
+    responces: Vec<AppResponce>,
+    clock: MockClock,
+}
+
+impl<A: AppHandler<MockWindow, MockSubwindow>> MockBackend<A> {
+    pub fn new(app: A) -> Self {
+        Self {
+            app,
+            windows: BTreeMap::new(),
+            subwindows: BTreeMap::new(),
+            responces: Vec::new(),
+            clock: MockClock::default(),
+        }
+    }
+
+    pub fn app(&self) -> &A {
+        &self.app
+    }
+
+    pub fn app_mut(&mut self) -> &mut A {
+        &mut self.app
+    }
+
+    /// Creates a window with the app's default parameters, like real backends do on start
+    pub fn start(&mut self) -> WindowId {
+        let params = self.app.default_window_params();
+        self.create_window(params)
+    }
+
+    pub fn create_window(&mut self, params: WindowParams) -> WindowId {
+        let id = WindowId::new();
+        let window = MockWindow::new(params);
+        self.windows.insert(id, window.clone());
+        let responce = self.app.handle_window_update(id, window);
+        self.handle_responce(responce);
+        id
+    }
+
+    pub fn create_subwindow(&mut self, parent: WindowId, params: SubwindowParams) -> WindowId {
+        let id = WindowId::new();
+        let window = MockSubwindow::new(parent, params);
+        self.subwindows.insert(id, window.clone());
+        let responce = self.app.handle_subwindow_update(id, window);
+        self.handle_responce(responce);
+        id
+    }
+
+    /// Repeats window updates for all windows, like real backends do after a resume
+    pub fn resume(&mut self) {
+        let windows = self.windows.clone();
+        for (id, window) in windows {
+            let responce = self.app.handle_window_update(id, window);
+            self.handle_responce(responce);
+        }
+        let subwindows = self.subwindows.clone();
+        for (id, window) in subwindows {
+            let responce = self.app.handle_subwindow_update(id, window);
+            self.handle_responce(responce);
+        }
+    }
+
+    /// Events for closed windows are dropped, as real backends never deliver them
+    pub fn send(&mut self, id: WindowId, event: WindowEvent) {
+        if !self.windows.contains_key(&id) && !self.subwindows.contains_key(&id) {
+            return;
+        }
+        let responce = self.app.handle_window_event(id, event);
+        self.handle_responce(responce);
+    }
+
+    pub fn send_all(&mut self, id: WindowId, events: impl IntoIterator<Item = WindowEvent>) {
+        for event in events {
+            self.send(id, event);
+        }
+    }
+
+    /// Advances the fake clock and delivers `Redraw` to windows that requested it since the last
+    /// frame, like a display refresh would
+    pub fn advance(&mut self, by: Duration) {
+        self.clock.set(self.clock.now() + by);
+        let pending = self
+            .windows
+            .iter()
+            .filter(|(_, window)| window.take_redraw())
+            .map(|(id, _)| *id)
+            .chain(
+                self.subwindows
+                    .iter()
+                    .filter(|(_, window)| window.take_redraw())
+                    .map(|(id, _)| *id),
+            )
+            .collect::<Vec<_>>();
+        for id in pending {
+            self.send(id, WindowEvent::Redraw);
+        }
+    }
+
+    /// Time passed since the backend was created
+    pub fn now(&self) -> Duration {
+        self.clock.now()
+    }
+
+    /// Handle to the fake clock, for apps that take their time from it
+    pub fn clock(&self) -> MockClock {
+        self.clock.clone()
+    }
+
+    /// Moves the clock without delivering redraws, for following recorded timestamps
+    pub fn set_now(&mut self, now: Duration) {
+        self.clock.set(now);
+    }
+
+    pub fn window(&self, id: WindowId) -> Option<&MockWindow> {
+        self.windows.get(&id)
+    }
+
+    pub fn subwindow(&self, id: WindowId) -> Option<&MockSubwindow> {
+        self.subwindows.get(&id)
+    }
+
+    /// Open windows in the order of creation
+    pub fn window_ids(&self) -> impl Iterator<Item = WindowId> + '_ {
+        self.windows.keys().copied()
+    }
+
+    pub fn subwindow_ids(&self) -> impl Iterator<Item = WindowId> + '_ {
+        self.subwindows.keys().copied()
+    }
+
+    pub fn responces(&self) -> &[AppResponce] {
+        &self.responces
+    }
+
+    pub fn take_responces(&mut self) -> Vec<AppResponce> {
+        std::mem::take(&mut self.responces)
+    }
+
+    fn handle_responce(&mut self, responce: AppResponce) {
+        // Recorded first, so that responces to the new windows come after it
+        match &responce {
+            AppResponce::CloseWindow(id) => {
+                let id = *id;
+                self.responces.push(responce);
+                self.close_window(id);
+            }
+            AppResponce::CreateNewWindow(params) => {
+                let params = params.clone();
+                self.responces.push(responce);
+                self.create_window(params);
+            }
+            AppResponce::CreateNewSubwindow { parent, params } => {
+                let (parent, params) = (*parent, params.clone());
+                self.responces.push(responce);
+                self.create_subwindow(parent, params);
+            }
+            AppResponce::Handled | AppResponce::Error(_) => self.responces.push(responce),
+        }
+    }
+
+    fn close_window(&mut self, id: WindowId) {
+        self.windows.remove(&id);
+        self.subwindows
+            .retain(|subwindow_id, window| *subwindow_id != id && window.state().parent != id);
+    }
+}
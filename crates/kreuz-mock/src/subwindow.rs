@@ -0,0 +1,63 @@
+use kreuz_window::{SubwindowHandler, SubwindowParams, WindowId};
+use raw_window_handle::{
+    DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, WindowHandle,
+};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct MockSubwindowState {
+    pub parent: WindowId,
+    pub params: SubwindowParams,
+    pub redraw_requests: usize,
+    pub redraw_pending: bool,
+}
+
+#[derive(Clone)]
+pub struct MockSubwindow {
+    state: Arc<Mutex<MockSubwindowState>>,
+}
+
+impl MockSubwindow {
+    pub fn new(parent: WindowId, params: SubwindowParams) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MockSubwindowState {
+                parent,
+                params,
+                redraw_requests: 0,
+                redraw_pending: false,
+            })),
+        }
+    }
+
+    pub fn state(&self) -> MockSubwindowState {
+        self.state.lock().unwrap().clone()
+    }
+
+    pub(crate) fn take_redraw(&self) -> bool {
+        std::mem::take(&mut self.state.lock().unwrap().redraw_pending)
+    }
+}
+
+impl HasWindowHandle for MockSubwindow {
+    fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+        Err(HandleError::Unavailable)
+    }
+}
+
+impl HasDisplayHandle for MockSubwindow {
+    fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        Err(HandleError::Unavailable)
+    }
+}
+
+impl SubwindowHandler for MockSubwindow {
+    fn request_redraw(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.redraw_requests += 1;
+        state.redraw_pending = true;
+    }
+
+    fn get_params(&self) -> SubwindowParams {
+        self.state.lock().unwrap().params.clone()
+    }
+}
@@ -0,0 +1,48 @@
+use kreuz_ui::{RootView, Scene, ViewEvent, ViewResponce};
+use peniko::kurbo::Rect;
+
+/// Root view that draws nothing and records every event it gets
+#[derive(Clone, Debug)]
+pub struct RecordingView {
+    pub events: Vec<ViewEvent>,
+    /// Returned for every event
+    pub responce: ViewResponce,
+    pub ime_area: Option<Rect>,
+}
+
+impl RecordingView {
+    pub fn new() -> Self {
+        Self::with_responce(ViewResponce::Skipped)
+    }
+
+    pub fn with_responce(responce: ViewResponce) -> Self {
+        Self {
+            events: Vec::new(),
+            responce,
+            ime_area: None,
+        }
+    }
+
+    pub fn take_events(&mut self) -> Vec<ViewEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+impl Default for RecordingView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RootView for RecordingView {
+    fn render(&self, _scene: &mut Scene) {}
+
+    fn handle_event(&mut self, event: &ViewEvent) -> ViewResponce {
+        self.events.push(event.clone());
+        self.responce.clone()
+    }
+
+    fn ime_area(&self) -> Option<Rect> {
+        self.ime_area
+    }
+}
@@ -0,0 +1,150 @@
+use kreuz_window::{
+    Fullscreen, Icon, Theme, WindowButtons, WindowHandler, WindowLevel, WindowParams,
+};
+use peniko::kurbo::{Point, Size};
+use raw_window_handle::{
+    DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, WindowHandle,
+};
+use std::sync::{Arc, Mutex};
+
+/// Everything the app did to a mock window
+#[derive(Clone, PartialEq, Debug)]
+pub struct MockWindowState {
+    pub params: WindowParams,
+    /// Number of `request_redraw` calls over the whole window lifetime
+    pub redraw_requests: usize,
+    /// Whether a redraw was requested since the last delivered `Redraw` event
+    pub redraw_pending: bool,
+    pub ime_allowed: bool,
+    pub ime_cursor_area: Option<(Point, Size)>,
+}
+
+/// Window without a display, it has no raw handles, so surfaces can't be created for it
+#[derive(Clone)]
+pub struct MockWindow {
+    state: Arc<Mutex<MockWindowState>>,
+}
+
+impl MockWindow {
+    pub fn new(params: WindowParams) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(MockWindowState {
+                params,
+                redraw_requests: 0,
+                redraw_pending: false,
+                ime_allowed: false,
+                ime_cursor_area: None,
+            })),
+        }
+    }
+
+    pub fn state(&self) -> MockWindowState {
+        self.state.lock().unwrap().clone()
+    }
+
+    /// Returns whether a redraw was pending
+    pub(crate) fn take_redraw(&self) -> bool {
+        std::mem::take(&mut self.state.lock().unwrap().redraw_pending)
+    }
+
+    fn update(&self, f: impl FnOnce(&mut MockWindowState)) {
+        f(&mut self.state.lock().unwrap());
+    }
+}
+
+impl HasWindowHandle for MockWindow {
+    fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+        Err(HandleError::Unavailable)
+    }
+}
+
+impl HasDisplayHandle for MockWindow {
+    fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        Err(HandleError::Unavailable)
+    }
+}
+
+impl WindowHandler for MockWindow {
+    fn request_redraw(&self) {
+        self.update(|state| {
+            state.redraw_requests += 1;
+            state.redraw_pending = true;
+        });
+    }
+
+    fn set_title(&self, title: &str) {
+        self.update(|state| state.params.title = title.into());
+    }
+
+    fn get_params(&self) -> WindowParams {
+        self.state.lock().unwrap().params.clone()
+    }
+
+    fn set_min_size(&self, size: Option<Size>) {
+        self.update(|state| state.params.min_size = size);
+    }
+
+    fn set_max_size(&self, size: Option<Size>) {
+        self.update(|state| state.params.max_size = size);
+    }
+
+    fn set_resizable(&self, resizable: bool) {
+        self.update(|state| state.params.resizable = resizable);
+    }
+
+    fn set_enabled_buttons(&self, buttons: WindowButtons) {
+        self.update(|state| state.params.enabled_buttons = buttons);
+    }
+
+    fn set_maximized(&self, maximized: bool) {
+        self.update(|state| state.params.maximized = maximized);
+    }
+
+    fn set_visible(&self, visible: bool) {
+        self.update(|state| state.params.visible = visible);
+    }
+
+    fn set_transparent(&self, transparent: bool) {
+        self.update(|state| state.params.transparent = transparent);
+    }
+
+    fn set_blur(&self, blur: bool) {
+        self.update(|state| state.params.blur = blur);
+    }
+
+    fn set_decorations(&self, decorations: bool) {
+        self.update(|state| state.params.decorations = decorations);
+    }
+
+    fn set_window_icon(&self, icon: Option<Icon>) {
+        self.update(|state| state.params.window_icon = icon);
+    }
+
+    fn set_theme(&self, theme: Option<Theme>) {
+        self.update(|state| state.params.preferred_theme = theme);
+    }
+
+    fn set_resize_increments(&self, increments: Option<Size>) {
+        self.update(|state| state.params.resize_increments = increments);
+    }
+
+    fn set_content_protected(&self, protected: bool) {
+        self.update(|state| state.params.content_protected = protected);
+    }
+
+    fn set_window_level(&self, level: WindowLevel) {
+        self.update(|state| state.params.window_level = level);
+    }
+
+    fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        self.update(|state| state.params.fullscreen = fullscreen);
+    }
+
+    fn set_ime_allowed(&self, allowed: bool) {
+        self.update(|state| state.ime_allowed = allowed);
+    }
+
+    fn set_ime_cursor_area(&self, position: Point, size: Size) {
+        self.update(|state| state.ime_cursor_area = Some((position, size)));
+    }
+}
@@ -0,0 +1,7 @@
+mod record;
+mod replay;
+mod trace;
+
+pub use record::*;
+pub use replay::*;
+pub use trace::*;
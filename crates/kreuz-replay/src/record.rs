@@ -0,0 +1,99 @@
+use anyhow::Result;
+use kreuz_window::{
+    AppHandler, AppResponce, SubwindowHandler, SubwindowParams, WindowEvent, WindowHandler,
+    WindowId, WindowParams,
+};
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    time::Instant,
+};
+
+use super::TraceEntry;
+
+/// Wraps an app and writes everything delivered to it into a trace file.
+///
+/// Every entry is a line of JSON flushed right away, so the trace of a crashed app is complete.
+/// Failing to write is reported as `AppResponce::Error`
+pub struct Recorder<A> {
+    app: A,
+    writer: BufWriter<File>,
+    start: Instant,
+    /// Windows that already got an update, later updates come after resumes
+    known_windows: HashSet<WindowId>,
+}
+
+impl<A> Recorder<A> {
+    pub fn new(app: A, path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self {
+            app,
+            writer: BufWriter::new(File::create(path)?),
+            start: Instant::now(),
+            known_windows: HashSet::new(),
+        })
+    }
+
+    pub fn into_inner(self) -> A {
+        self.app
+    }
+
+    fn write(&mut self, entry: &TraceEntry) -> Result<()> {
+        serde_json::to_writer(&mut self.writer, entry)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+impl<W: WindowHandler, SW: SubwindowHandler, A: AppHandler<W, SW>> AppHandler<W, SW>
+    for Recorder<A>
+{
+    fn default_window_params(&self) -> WindowParams {
+        self.app.default_window_params()
+    }
+
+    fn default_subwindow_params(&self) -> SubwindowParams {
+        self.app.default_subwindow_params()
+    }
+
+    fn handle_window_event(&mut self, window: WindowId, event: WindowEvent) -> AppResponce {
+        let entry = TraceEntry::Event {
+            time: self.start.elapsed(),
+            window,
+            event: event.clone(),
+        };
+        if let Err(err) = self.write(&entry) {
+            return AppResponce::Error(err.into());
+        }
+        self.app.handle_window_event(window, event)
+    }
+
+    fn handle_window_update(&mut self, id: WindowId, window: W) -> AppResponce {
+        if self.known_windows.insert(id) {
+            let entry = TraceEntry::Window {
+                time: self.start.elapsed(),
+                window: id,
+                params: window.get_params(),
+            };
+            if let Err(err) = self.write(&entry) {
+                return AppResponce::Error(err.into());
+            }
+        }
+        self.app.handle_window_update(id, window)
+    }
+
+    fn handle_subwindow_update(&mut self, id: WindowId, window: SW) -> AppResponce {
+        if self.known_windows.insert(id) {
+            let entry = TraceEntry::Subwindow {
+                time: self.start.elapsed(),
+                window: id,
+            };
+            if let Err(err) = self.write(&entry) {
+                return AppResponce::Error(err.into());
+            }
+        }
+        self.app.handle_subwindow_update(id, window)
+    }
+}
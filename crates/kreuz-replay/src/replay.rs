@@ -0,0 +1,48 @@
+use kreuz_mock::{MockBackend, MockSubwindow, MockWindow};
+use kreuz_window::{AppHandler, WindowId};
+use std::collections::HashMap;
+
+use super::TraceEntry;
+
+/// Replays a trace against an app driven by the mock backend, the mock clock follows the
+/// recorded timestamps, so apps timed by `MockBackend::clock` see them too. Returns ids of the
+/// mock windows by the recorded ones.
+///
+/// Recorded windows are matched with the mock ones in the order of creation, windows that the
+/// app doesn't create by itself are created with the recorded parameters. Events for windows
+/// that don't exist during the replay are skipped
+pub fn replay<A: AppHandler<MockWindow, MockSubwindow>>(
+    backend: &mut MockBackend<A>,
+    trace: &[TraceEntry],
+) -> HashMap<WindowId, WindowId> {
+    let mut ids = HashMap::new();
+    for entry in trace {
+        backend.set_now(entry.time());
+        match entry {
+            TraceEntry::Window { window, params, .. } => {
+                let unmatched = backend
+                    .window_ids()
+                    .find(|id| !ids.values().any(|matched| matched == id));
+                let id = match unmatched {
+                    Some(id) => id,
+                    None => backend.create_window(params.clone()),
+                };
+                ids.insert(*window, id);
+            }
+            TraceEntry::Subwindow { window, .. } => {
+                let unmatched = backend
+                    .subwindow_ids()
+                    .find(|id| !ids.values().any(|matched| matched == id));
+                if let Some(id) = unmatched {
+                    ids.insert(*window, id);
+                }
+            }
+            TraceEntry::Event { window, event, .. } => {
+                if let Some(id) = ids.get(window) {
+                    backend.send(*id, event.clone());
+                }
+            }
+        }
+    }
+    ids
+}
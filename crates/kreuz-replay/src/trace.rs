@@ -0,0 +1,54 @@
+use anyhow::{Context, Result};
+use kreuz_window::{WindowEvent, WindowId, WindowParams};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+    time::Duration,
+};
+
+/// Single record of a trace, `time` is counted from the start of recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TraceEntry {
+    /// Window got its first update
+    Window {
+        time: Duration,
+        window: WindowId,
+        params: WindowParams,
+    },
+    /// Subwindow got its first update
+    Subwindow { time: Duration, window: WindowId },
+    Event {
+        time: Duration,
+        window: WindowId,
+        event: WindowEvent,
+    },
+}
+
+impl TraceEntry {
+    pub fn time(&self) -> Duration {
+        match self {
+            TraceEntry::Window { time, .. }
+            | TraceEntry::Subwindow { time, .. }
+            | TraceEntry::Event { time, .. } => *time,
+        }
+    }
+}
+
+/// Reads a trace written by `Recorder`
+pub fn read_trace(path: impl AsRef<Path>) -> Result<Vec<TraceEntry>> {
+    let path = path.as_ref();
+    let file = File::open(path).with_context(|| format!("can't open {}", path.display()))?;
+    let mut entries = Vec::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = serde_json::from_str(&line)
+            .with_context(|| format!("{}:{}: invalid trace entry", path.display(), index + 1))?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
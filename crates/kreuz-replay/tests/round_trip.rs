@@ -0,0 +1,144 @@
+use kreuz_mock::{MockBackend, MockSubwindow, MockWindow, RecordingView};
+use kreuz_replay::{read_trace, replay, Recorder, TraceEntry};
+use kreuz_ui::{ViewEvent, ViewResponce};
+use kreuz_vello::{make_vello_app, VelloApp};
+use kreuz_window::{
+    AppHandler, AppResponce, ButtonState, ImeEvent, Key, KeyCode, KeyEvent, KeyLocation,
+    MouseButton, WindowEvent, WindowId, WindowParams,
+};
+use peniko::kurbo::{Point, Size};
+use std::{
+    env, fs,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+type Events = Arc<Mutex<Vec<WindowEvent>>>;
+
+/// Keeps every event the app got, shared so that it can be read after the app is gone.
+/// The app animates all the time, so that every redraw gets an animation frame
+struct EventLog {
+    events: Events,
+    app: VelloApp<'static, MockWindow, RecordingView>,
+}
+
+impl EventLog {
+    fn new(events: &Events) -> Self {
+        Self {
+            events: events.clone(),
+            app: make_vello_app(|_| RecordingView::with_responce(ViewResponce::Animate)),
+        }
+    }
+}
+
+impl AppHandler<MockWindow, MockSubwindow> for EventLog {
+    fn handle_window_event(&mut self, window: WindowId, event: WindowEvent) -> AppResponce {
+        self.events.lock().unwrap().push(event.clone());
+        AppHandler::<MockWindow, MockSubwindow>::handle_window_event(&mut self.app, window, event)
+    }
+
+    fn handle_window_update(&mut self, id: WindowId, window: MockWindow) -> AppResponce {
+        AppHandler::<MockWindow, MockSubwindow>::handle_window_update(&mut self.app, id, window)
+    }
+
+    fn handle_subwindow_update(&mut self, id: WindowId, window: MockSubwindow) -> AppResponce {
+        AppHandler::<MockWindow, MockSubwindow>::handle_subwindow_update(&mut self.app, id, window)
+    }
+}
+
+fn events() -> Vec<WindowEvent> {
+    vec![
+        WindowEvent::Focused { focused: true },
+        WindowEvent::Resize {
+            new_size: Size::new(640., 480.),
+        },
+        WindowEvent::CursorMove {
+            pos: Point::new(12.5, 40.),
+        },
+        WindowEvent::MouseButton {
+            button: MouseButton::Left,
+            state: ButtonState::Pressed,
+        },
+        WindowEvent::KeyDown {
+            event: KeyEvent {
+                key: Key::Character("q".into()),
+                code: KeyCode::KeyQ,
+                location: KeyLocation::Standard,
+                repeat: false,
+                text: Some("q".into()),
+            },
+        },
+        WindowEvent::Ime {
+            event: ImeEvent::Commit { text: "ü".into() },
+        },
+        WindowEvent::CloseRequested,
+        WindowEvent::Redraw,
+        WindowEvent::Redraw,
+    ]
+}
+
+#[test]
+fn replay_delivers_recorded_events() {
+    let path = env::temp_dir().join(format!("kreuz-replay-{}.jsonl", std::process::id()));
+    let params = WindowParams {
+        size: Size::new(320., 200.),
+        title: "Recorded".into(),
+        ..Default::default()
+    };
+
+    let recorded = Events::default();
+    let app = Recorder::new(EventLog::new(&recorded), &path).unwrap();
+    let mut backend = MockBackend::new(app);
+    let id = backend.create_window(params.clone());
+    backend.send_all(
+        id,
+        events()
+            .into_iter()
+            .filter(|event| *event != WindowEvent::Redraw),
+    );
+    // Redraws come from the backend, paced by the clock
+    backend.advance(Duration::from_millis(16));
+    backend.advance(Duration::from_millis(16));
+    drop(backend);
+
+    let trace = read_trace(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let replayed = Events::default();
+    let mut backend = MockBackend::new(EventLog::new(&replayed));
+    let clock = backend.clock();
+    backend.app_mut().app.set_clock(move || clock.now());
+    let ids = replay(&mut backend, &trace);
+    assert_eq!(ids.len(), 1);
+    assert_eq!(backend.window(ids[&id]).unwrap().state().params, params);
+
+    assert_eq!(*recorded.lock().unwrap(), events());
+    assert_eq!(*replayed.lock().unwrap(), events());
+
+    // Animation frames of the replay are timed by the recorded redraws
+    let redraw_times = trace
+        .iter()
+        .filter_map(|entry| match entry {
+            TraceEntry::Event {
+                time,
+                event: WindowEvent::Redraw,
+                ..
+            } => Some(*time),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let frame_times = backend
+        .app()
+        .app
+        .view(ids[&id])
+        .unwrap()
+        .events
+        .iter()
+        .filter_map(|event| match event {
+            ViewEvent::AnimationFrame { time } => Some(*time),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(redraw_times.len(), 2);
+    assert_eq!(frame_times, redraw_times);
+}
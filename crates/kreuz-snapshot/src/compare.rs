@@ -0,0 +1,134 @@
+use image::{Rgba, RgbaImage};
+use std::fmt;
+
+/// Pixels that differ are painted red in the diff image
+const DIFF_COLOR: Rgba<u8> = Rgba([0xff, 0, 0, 0xff]);
+
+pub struct Comparison {
+    pub reference_size: (u32, u32),
+    pub actual_size: (u32, u32),
+    pub differing_pixels: usize,
+    /// Biggest channel difference over all pixels
+    pub max_difference: u8,
+    /// Reference image dimmed to gray with differing pixels highlighted, `None` if sizes differ
+    pub diff: Option<RgbaImage>,
+}
+
+impl Comparison {
+    pub fn is_match(&self) -> bool {
+        self.reference_size == self.actual_size && self.differing_pixels == 0
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.reference_size != self.actual_size {
+            let (rw, rh) = self.reference_size;
+            let (aw, ah) = self.actual_size;
+            return write!(f, "expected size {rw}x{rh}, got {aw}x{ah}");
+        }
+        write!(
+            f,
+            "{} pixels differ, max channel difference is {}",
+            self.differing_pixels, self.max_difference
+        )
+    }
+}
+
+/// Compares images pixel by pixel, channels that differ by at most `tolerance` are equal
+pub fn compare(reference: &RgbaImage, actual: &RgbaImage, tolerance: u8) -> Comparison {
+    let mut comparison = Comparison {
+        reference_size: reference.dimensions(),
+        actual_size: actual.dimensions(),
+        differing_pixels: 0,
+        max_difference: 0,
+        diff: None,
+    };
+    if comparison.reference_size != comparison.actual_size {
+        return comparison;
+    }
+
+    let (width, height) = reference.dimensions();
+    let mut diff = RgbaImage::new(width, height);
+    for ((expected, got), out) in reference
+        .pixels()
+        .zip(actual.pixels())
+        .zip(diff.pixels_mut())
+    {
+        let difference = expected
+            .0
+            .iter()
+            .zip(got.0)
+            .map(|(a, b)| a.abs_diff(b))
+            .max()
+            .unwrap_or(0);
+        comparison.max_difference = comparison.max_difference.max(difference);
+        *out = if difference > tolerance {
+            comparison.differing_pixels += 1;
+            DIFF_COLOR
+        } else {
+            let [r, g, b, _] = expected.0;
+            let luma = ((r as u32 * 3 + g as u32 * 6 + b as u32) / 10) as u8;
+            // Dimmed so that highlighted pixels stand out
+            let gray = luma / 4 + 0x40;
+            Rgba([gray, gray, gray, 0xff])
+        };
+    }
+    comparison.diff = Some(diff);
+    comparison
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filled(width: u32, height: u32, color: [u8; 4]) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba(color))
+    }
+
+    #[test]
+    fn identical_images_match() {
+        let image = filled(4, 3, [10, 20, 30, 255]);
+        let comparison = compare(&image, &image, 0);
+        assert!(comparison.is_match());
+        assert_eq!(comparison.differing_pixels, 0);
+        assert_eq!(comparison.max_difference, 0);
+        assert!(comparison.diff.is_some());
+    }
+
+    #[test]
+    fn differences_within_tolerance_match() {
+        let reference = filled(4, 3, [10, 20, 30, 255]);
+        let actual = filled(4, 3, [12, 19, 30, 255]);
+        let comparison = compare(&reference, &actual, 2);
+        assert!(comparison.is_match());
+        assert_eq!(comparison.max_difference, 2);
+    }
+
+    #[test]
+    fn size_mismatch() {
+        let reference = filled(4, 3, [0, 0, 0, 255]);
+        let actual = filled(3, 4, [0, 0, 0, 255]);
+        let comparison = compare(&reference, &actual, 255);
+        assert!(!comparison.is_match());
+        assert!(comparison.diff.is_none());
+        assert_eq!(comparison.to_string(), "expected size 4x3, got 3x4");
+    }
+
+    #[test]
+    fn differences_over_tolerance_are_highlighted() {
+        let reference = filled(4, 3, [10, 20, 30, 255]);
+        let mut actual = reference.clone();
+        actual.put_pixel(1, 2, Rgba([10, 20, 34, 255]));
+        actual.put_pixel(3, 0, Rgba([0, 20, 30, 255]));
+        let comparison = compare(&reference, &actual, 3);
+        assert!(!comparison.is_match());
+        assert_eq!(comparison.differing_pixels, 2);
+        assert_eq!(comparison.max_difference, 10);
+
+        let diff = comparison.diff.unwrap();
+        assert_eq!(*diff.get_pixel(1, 2), DIFF_COLOR);
+        assert_eq!(*diff.get_pixel(3, 0), DIFF_COLOR);
+        assert_ne!(*diff.get_pixel(0, 0), DIFF_COLOR);
+    }
+}
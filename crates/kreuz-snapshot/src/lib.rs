@@ -0,0 +1,92 @@
+mod compare;
+
+pub use compare::*;
+
+use anyhow::{bail, Context, Result};
+use image::RgbaImage;
+use kreuz_ui::RootView;
+use kreuz_vello::HeadlessRenderer;
+use peniko::kurbo::Size;
+use std::{env, path::PathBuf};
+
+/// References are created or rewritten instead of compared when this variable is set
+pub const UPDATE_ENV: &str = "KREUZ_UPDATE_SNAPSHOTS";
+
+/// Renders views offscreen and compares them against reference PNGs stored in `dir`.
+///
+/// A missing reference is an error unless `KREUZ_UPDATE_SNAPSHOTS` is set, so that CI doesn't
+/// silently accept new snapshots. On mismatch, the rendering and an image highlighting differing
+/// pixels are written next to the reference as `<name>.actual.png` and `<name>.diff.png`
+pub struct SnapshotTester {
+    renderer: HeadlessRenderer,
+    pub dir: PathBuf,
+    pub scale: f64,
+    /// Maximal difference of a single channel for pixels to be considered equal
+    pub tolerance: u8,
+}
+
+impl SnapshotTester {
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self> {
+        Ok(Self {
+            renderer: HeadlessRenderer::new()?,
+            dir: dir.into(),
+            scale: 1.,
+            tolerance: 2,
+        })
+    }
+
+    pub fn render(&mut self, view: &mut impl RootView, size: Size) -> Result<RgbaImage> {
+        let image = self.renderer.render(view, size, self.scale)?;
+        RgbaImage::from_raw(image.width, image.height, image.data.data().to_vec())
+            .context("rendered image has unexpected size")
+    }
+
+    /// Returns an error describing the mismatch if the rendering differs from the reference
+    pub fn check(&mut self, name: &str, view: &mut impl RootView, size: Size) -> Result<()> {
+        let actual = self.render(view, size)?;
+        let reference_path = self.dir.join(format!("{name}.png"));
+        let actual_path = self.dir.join(format!("{name}.actual.png"));
+        let diff_path = self.dir.join(format!("{name}.diff.png"));
+
+        if env::var_os(UPDATE_ENV).is_some() {
+            std::fs::create_dir_all(&self.dir)?;
+            actual.save(&reference_path)?;
+            return Ok(());
+        }
+        if !reference_path.exists() {
+            bail!(
+                "snapshot `{name}` has no reference {}, run with {UPDATE_ENV}=1 to create it",
+                reference_path.display(),
+            );
+        }
+
+        let reference = image::open(&reference_path)
+            .with_context(|| format!("can't open {}", reference_path.display()))?
+            .into_rgba8();
+        let comparison = compare(&reference, &actual, self.tolerance);
+        if comparison.is_match() {
+            // Leftovers of previous failures would be confusing
+            let _ = std::fs::remove_file(&actual_path);
+            let _ = std::fs::remove_file(&diff_path);
+            return Ok(());
+        }
+
+        actual.save(&actual_path)?;
+        if let Some(diff) = &comparison.diff {
+            diff.save(&diff_path)?;
+        }
+        bail!(
+            "snapshot `{name}` doesn't match {}: {comparison}, see {}",
+            reference_path.display(),
+            actual_path.display(),
+        )
+    }
+
+    /// Same as `check`, but panics on mismatch, for use in tests
+    #[track_caller]
+    pub fn assert(&mut self, name: &str, view: &mut impl RootView, size: Size) {
+        if let Err(err) = self.check(name, view, size) {
+            panic!("{err:#}");
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use kreuz_snapshot::SnapshotTester;
+use kreuz_ui::{RootView, Scene, ViewEvent, ViewResponce};
+use peniko::{
+    kurbo::{Affine, Circle, RoundedRect, Size, Stroke},
+    Color, Fill,
+};
+
+/// Card with a border and a badge, drawn in logical pixels
+struct Card;
+
+impl RootView for Card {
+    fn render(&self, scene: &mut Scene) {
+        let card = RoundedRect::new(8., 8., 88., 56., 6.);
+        scene.fill(Fill::NonZero, Affine::IDENTITY, Color::WHITE, None, &card);
+        scene.stroke(
+            &Stroke::new(2.),
+            Affine::IDENTITY,
+            Color::rgb8(0x30, 0x60, 0xc0),
+            None,
+            &card,
+        );
+        let badge = Circle::new((80., 16.), 6.);
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            Color::rgb8(0xe0, 0x40, 0x40),
+            None,
+            &badge,
+        );
+    }
+
+    fn handle_event(&mut self, _event: &ViewEvent) -> ViewResponce {
+        ViewResponce::Skipped
+    }
+}
+
+fn tester() -> SnapshotTester {
+    SnapshotTester::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots")).unwrap()
+}
+
+#[test]
+fn card() {
+    tester().assert("card", &mut Card, Size::new(96., 64.));
+}
+
+#[test]
+fn card_scaled() {
+    let mut tester = tester();
+    tester.scale = 2.;
+    tester.assert("card@2x", &mut Card, Size::new(96., 64.));
+}
@@ -1,12 +1,20 @@
 use kurbo::{Affine, Rect};
-use peniko::Color;
+use peniko::{Color, Fill};
 
 use super::*;
 
 pub struct DummyView;
 
 impl RootView for DummyView {
-    fn render(&self, scene: &mut Scene) {}
+    fn render(&self, scene: &mut Scene) {
+        scene.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            Color::rgb8(0x30, 0x60, 0xa0),
+            None,
+            &Rect::new(20., 20., 220., 120.),
+        );
+    }
 
     fn handle_event(&mut self, event: &ViewEvent) -> ViewResponce {
         println!("{event:?}");
@@ -0,0 +1,17 @@
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ImeEvent {
+    /// IME was enabled, preedit and commit events may be sent after this one
+    Enabled,
+    /// Text that is being composed, not committed yet.
+    ///
+    /// Cursor is a byte-wise range of the text where the caret (or the selection) is, `None`
+    /// means that cursor should be hidden. Empty text means that the preedit was cleared.
+    Preedit {
+        text: String,
+        cursor: Option<(usize, usize)>,
+    },
+    /// Text that should be inserted at the cursor position
+    Commit { text: String },
+    /// IME was disabled, preedit should be cleared
+    Disabled,
+}
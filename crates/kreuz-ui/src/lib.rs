@@ -1,9 +1,13 @@
 mod dummy;
+mod ime;
+mod keyboard;
 mod scene;
 mod view;
 mod view_event;
 
 pub use dummy::*;
+pub use ime::*;
+pub use keyboard::*;
 pub use scene::*;
 pub use view::*;
 pub use view_event::*;
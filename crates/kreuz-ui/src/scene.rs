@@ -1 +1,162 @@
-pub struct Scene {}
+use kurbo::{Affine, BezPath, Shape, Stroke};
+use peniko::{BlendMode, Brush, BrushRef, Fill, Font, Image, Style};
+
+/// Tolerance used to flatten shapes into paths
+const SHAPE_TOLERANCE: f64 = 0.1;
+
+/// Recorded list of drawing commands, renderers translate it into their own representation
+#[derive(Clone, Default)]
+pub struct Scene {
+    commands: Vec<SceneCommand>,
+}
+
+#[derive(Clone)]
+pub enum SceneCommand {
+    Fill {
+        style: Fill,
+        transform: Affine,
+        brush: Brush,
+        brush_transform: Option<Affine>,
+        shape: BezPath,
+    },
+    Stroke {
+        style: Stroke,
+        transform: Affine,
+        brush: Brush,
+        brush_transform: Option<Affine>,
+        shape: BezPath,
+    },
+    /// Everything drawn until the matching `PopLayer` is clipped by `clip` and blended with
+    /// the content below
+    PushLayer {
+        blend: BlendMode,
+        alpha: f32,
+        transform: Affine,
+        clip: BezPath,
+    },
+    PopLayer,
+    Image {
+        image: Image,
+        transform: Affine,
+    },
+    Glyphs(GlyphRun),
+}
+
+#[derive(Clone)]
+pub struct GlyphRun {
+    pub font: Font,
+    pub font_size: f32,
+    pub transform: Affine,
+    pub glyph_transform: Option<Affine>,
+    /// Variable font coordinates, raw `F2Dot14` values
+    pub normalized_coords: Vec<i16>,
+    pub brush: Brush,
+    pub style: Style,
+    pub hint: bool,
+    pub glyphs: Vec<Glyph>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Glyph {
+    pub id: u32,
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Scene {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn reset(&mut self) {
+        self.commands.clear();
+    }
+
+    pub fn commands(&self) -> &[SceneCommand] {
+        &self.commands
+    }
+
+    pub fn fill<'b>(
+        &mut self,
+        style: Fill,
+        transform: Affine,
+        brush: impl Into<BrushRef<'b>>,
+        brush_transform: Option<Affine>,
+        shape: &impl Shape,
+    ) {
+        self.commands.push(SceneCommand::Fill {
+            style,
+            transform,
+            brush: brush.into().to_owned(),
+            brush_transform,
+            shape: shape.to_path(SHAPE_TOLERANCE),
+        });
+    }
+
+    pub fn stroke<'b>(
+        &mut self,
+        style: &Stroke,
+        transform: Affine,
+        brush: impl Into<BrushRef<'b>>,
+        brush_transform: Option<Affine>,
+        shape: &impl Shape,
+    ) {
+        self.commands.push(SceneCommand::Stroke {
+            style: style.clone(),
+            transform,
+            brush: brush.into().to_owned(),
+            brush_transform,
+            shape: shape.to_path(SHAPE_TOLERANCE),
+        });
+    }
+
+    pub fn push_layer(
+        &mut self,
+        blend: impl Into<BlendMode>,
+        alpha: f32,
+        transform: Affine,
+        clip: &impl Shape,
+    ) {
+        self.commands.push(SceneCommand::PushLayer {
+            blend: blend.into(),
+            alpha,
+            transform,
+            clip: clip.to_path(SHAPE_TOLERANCE),
+        });
+    }
+
+    pub fn pop_layer(&mut self) {
+        self.commands.push(SceneCommand::PopLayer);
+    }
+
+    pub fn draw_image(&mut self, image: &Image, transform: Affine) {
+        self.commands.push(SceneCommand::Image {
+            image: image.clone(),
+            transform,
+        });
+    }
+
+    pub fn draw_glyphs(&mut self, run: GlyphRun) {
+        self.commands.push(SceneCommand::Glyphs(run));
+    }
+
+    /// Appends commands of another scene, applying `transform` to all of them
+    pub fn append(&mut self, other: &Scene, transform: Option<Affine>) {
+        let Some(transform) = transform else {
+            self.commands.extend_from_slice(&other.commands);
+            return;
+        };
+        self.commands
+            .extend(other.commands.iter().cloned().map(|mut command| {
+                match &mut command {
+                    SceneCommand::Fill { transform: t, .. }
+                    | SceneCommand::Stroke { transform: t, .. }
+                    | SceneCommand::PushLayer { transform: t, .. }
+                    | SceneCommand::Image { transform: t, .. } => *t = transform * *t,
+                    SceneCommand::Glyphs(run) => run.transform = transform * run.transform,
+                    SceneCommand::PopLayer => {}
+                }
+                command
+            }));
+    }
+}
@@ -1,12 +1,25 @@
+use peniko::kurbo::Rect;
+
 use super::*;
 
+#[derive(Debug, Clone, PartialEq)]
 pub enum ViewResponce {
     Skipped,
     Handled,
+    /// Event was handled and the view has to be drawn again
+    Repaint,
+    /// Like `Repaint`, and the view gets `ViewEvent::AnimationFrame` before the next frame
+    Animate,
 }
 
 pub trait RootView {
     fn render(&self, scene: &mut Scene);
 
     fn handle_event(&mut self, event: &ViewEvent) -> ViewResponce;
+
+    /// Area of the focused text input in logical pixels. The input method is enabled while it's
+    /// `Some` and places its candidate window near the area, it's checked after every event
+    fn ime_area(&self) -> Option<Rect> {
+        None
+    }
 }
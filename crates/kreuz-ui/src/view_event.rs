@@ -1,15 +1,107 @@
-use peniko::kurbo::Point;
+use peniko::kurbo::{Point, Size, Vec2};
+use std::time::Duration;
 
-#[derive(Debug, Clone)]
+use super::*;
+
+/// Positions, sizes and pixel deltas are in logical pixels, the scene is scaled by the renderer
+#[derive(Debug, Clone, PartialEq)]
 pub enum ViewEvent {
+    /// Sent when the view is shown and when its size or scale factor changes
+    Resize {
+        size: Size,
+        scale: f64,
+    },
+
+    /// Sent before drawing a frame if the view responded with `ViewResponce::Animate`, `time` is
+    /// counted from the start of the app
+    AnimationFrame {
+        time: Duration,
+    },
+
+    /// Window is going to be closed, any responce other than `ViewResponce::Skipped` keeps it open
+    CloseRequested,
+
+    Focused {
+        focused: bool,
+    },
+
+    ThemeChanged {
+        theme: Theme,
+    },
+
     CursorEntered,
     CursorLeft,
 
-    CursorMove { pos: Point },
+    CursorMove {
+        pos: Point,
+    },
+
+    MouseButtonPress {
+        pos: Point,
+        button: MouseButton,
+    },
+
+    MouseButtonRelease {
+        pos: Point,
+        button: MouseButton,
+    },
+
+    MouseWheel {
+        pos: Point,
+        delta: ScrollDelta,
+        phase: TouchPhase,
+    },
+
+    /// Touch with a finger, `id` stays the same for the whole lifetime of a touch
+    Touch {
+        id: u64,
+        pos: Point,
+        phase: TouchPhase,
+        /// Normalized pressure in range from 0.0 to 1.0, if the device can measure it
+        force: Option<f64>,
+    },
+
+    /// Two-finger pinch, positive delta means magnification
+    PinchGesture {
+        pos: Point,
+        delta: f64,
+        phase: TouchPhase,
+    },
+
+    /// Two-finger pan
+    PanGesture {
+        pos: Point,
+        delta: Vec2,
+        phase: TouchPhase,
+    },
 
-    MouseButtonPress { pos: Point, button: MouseButton },
+    /// Two-finger rotation, delta is in degrees, positive value means counterclockwise rotation
+    RotationGesture {
+        pos: Point,
+        delta: f64,
+        phase: TouchPhase,
+    },
 
-    MouseButtonRelease { pos: Point, button: MouseButton },
+    /// Two-finger double tap, used as "smart magnification" on touchpads
+    DoubleTapGesture {
+        pos: Point,
+    },
+
+    KeyDown {
+        event: KeyEvent,
+    },
+
+    KeyUp {
+        event: KeyEvent,
+    },
+
+    ModifiersChanged {
+        modifiers: Modifiers,
+    },
+
+    Ime {
+        event: ImeEvent,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -26,3 +118,25 @@ pub enum MouseButton {
     Back,
     Forward,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScrollDelta {
+    /// Amount of lines or rows to scroll, used by traditional mouse wheels
+    Lines(Vec2),
+    /// Amount of pixels to scroll, used by touchpads and other precise devices
+    Pixels(Vec2),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TouchPhase {
+    Started,
+    Moved,
+    Ended,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Theme {
+    Light,
+    Dark,
+}
@@ -0,0 +1,553 @@
+use anyhow::{anyhow, Context as _, Result};
+use kreuz_ui::{RootView, ViewEvent, ViewResponce};
+use kreuz_window::{
+    AppHandler, AppResponce, SubwindowHandler, WindowEvent, WindowHandler, WindowId,
+};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use vello::{
+    kurbo::{Affine, Rect},
+    util::{RenderContext, RenderSurface},
+    wgpu::{
+        self,
+        rwh::{HandleError, HasDisplayHandle, HasWindowHandle},
+    },
+    Renderer, RendererOptions, Scene,
+};
+
+use super::{
+    event::{window_to_view_event, Context},
+    scene::translate_scene,
+    FrameProfiler, FrameTimings, VelloOptions, VelloOptionsHandle,
+};
+
+// Simple struct to hold the state of the renderer
+pub struct ActiveRenderState<'s, W> {
+    // The fields MUST be in this order, so that the surface is dropped before the window
+    surface: RenderSurface<'s>,
+    window: Arc<W>,
+}
+
+enum RenderState<'s, W> {
+    Active(Box<ActiveRenderState<'s, W>>),
+    Suspended,
+}
+
+/// Everything that belongs to a single window
+struct WindowState<'s, W, V> {
+    render_state: RenderState<'s, W>,
+    /// Kept while suspended too, input and IME don't need a surface
+    window: Option<Arc<W>>,
+    ctx: Context,
+    root_view: V,
+    /// View changed since the last presented frame
+    dirty: bool,
+    /// Redraw was requested by the app, other redraws come from the backend and are always
+    /// presented, as the window contents may be lost
+    redraw_scheduled: bool,
+    /// View asked for `ViewEvent::AnimationFrame` before the next frame
+    animating: bool,
+    /// IME area last applied to the window, the input method is disabled while it's `None`
+    ime_area: Option<Rect>,
+}
+
+impl<W: WindowHandler, V: RootView> WindowState<'_, W, V> {
+    /// Passes the event to the view and schedules a frame if the view asks for it
+    fn send_view_event(&mut self, event: &ViewEvent) -> ViewResponce {
+        let responce = self.root_view.handle_event(event);
+        self.update_ime();
+        if let ViewResponce::Repaint | ViewResponce::Animate = responce {
+            self.animating |= matches!(responce, ViewResponce::Animate);
+            // Presenting waits for vsync, which paces animations requesting every frame
+            self.schedule_redraw();
+        }
+        responce
+    }
+
+    /// Marks the view as changed and asks the backend for a frame
+    fn schedule_redraw(&mut self) {
+        self.dirty = true;
+        if let RenderState::Active(_) = &self.render_state {
+            self.redraw_scheduled = true;
+        }
+        // Windows without a surface get frames too, so that their views keep animating
+        if let Some(window) = &self.window {
+            window.request_redraw();
+        }
+    }
+
+    /// Passes changes of the view's IME area to the window
+    fn update_ime(&mut self) {
+        let area = self.root_view.ime_area();
+        if area == self.ime_area {
+            return;
+        }
+        let Some(window) = &self.window else {
+            return;
+        };
+        if area.is_some() != self.ime_area.is_some() {
+            window.set_ime_allowed(area.is_some());
+        }
+        if let Some(area) = area {
+            window.set_ime_cursor_area(area.origin(), area.size());
+        }
+        self.ime_area = area;
+    }
+}
+
+pub struct VelloApp<'s, W: WindowHandler, V: RootView> {
+    context: RenderContext,
+    /// Renderers are shared between windows, one per device, indexed by `dev_id`
+    renderers: Vec<Option<Renderer>>,
+    windows: HashMap<WindowId, WindowState<'s, W, V>>,
+    scene: Scene,
+    /// Scene of the current view in logical pixels, appended to `scene` with the window scale
+    fragment: Scene,
+    /// Scene that root views draw to, translated into `fragment` every frame
+    ui_scene: kreuz_ui::Scene,
+    make_view: Box<dyn FnMut(WindowId) -> V + Send>,
+    /// Options currently in use
+    options: VelloOptions,
+    options_handle: VelloOptionsHandle,
+    /// Set by wgpu from its own thread, the context is rebuilt before the next event
+    device_lost: Arc<AtomicBool>,
+    /// Time since the start of the app, used for animation frame timestamps and frame timings
+    clock: Box<dyn Fn() -> Duration + Send>,
+    profiler: FrameProfiler,
+}
+
+impl<'s, W: WindowHandler + 's, V: RootView> VelloApp<'s, W, V> {
+    pub fn options(&self) -> &VelloOptions {
+        &self.options
+    }
+
+    /// Replaces the clock the app takes its time from, e.g. with `MockBackend::clock` in tests.
+    /// `clock` returns the time since the start of the app
+    pub fn set_clock(&mut self, clock: impl Fn() -> Duration + Send + 'static) {
+        self.clock = Box::new(clock);
+    }
+
+    /// Handle for changing options after the app was passed to a backend
+    pub fn options_handle(&self) -> VelloOptionsHandle {
+        self.options_handle.clone()
+    }
+
+    /// Root view of the window, `None` until the backend creates it
+    pub fn view(&self, id: WindowId) -> Option<&V> {
+        self.windows.get(&id).map(|state| &state.root_view)
+    }
+
+    pub fn view_mut(&mut self, id: WindowId) -> Option<&mut V> {
+        self.windows.get_mut(&id).map(|state| &mut state.root_view)
+    }
+
+    /// Timings of recent frames, collected while `VelloOptions::profiling` is enabled
+    pub fn profiler(&self) -> FrameProfiler {
+        self.profiler.clone()
+    }
+
+    pub fn set_options(&mut self, options: VelloOptions) -> Result<()> {
+        self.options_handle.set(options);
+        self.apply_options(options)
+    }
+
+    /// Catches up with changes made outside of the event loop
+    fn sync(&mut self) -> Result<()> {
+        if self.device_lost.load(Ordering::Relaxed) {
+            self.recover_from_device_loss()?;
+        }
+        let options = self.options_handle.get();
+        if options != self.options {
+            self.apply_options(options)?;
+        }
+        Ok(())
+    }
+
+    /// Applies new options to open windows, renderers are recreated only when their own
+    /// options change
+    fn apply_options(&mut self, options: VelloOptions) -> Result<()> {
+        let old = std::mem::replace(&mut self.options, options);
+        if old.use_cpu != options.use_cpu || old.num_init_threads != options.num_init_threads {
+            self.renderers.clear();
+        }
+        for state in self.windows.values_mut() {
+            let RenderState::Active(render_state) = &mut state.render_state else {
+                continue;
+            };
+            if old.present_mode != options.present_mode {
+                self.context
+                    .set_present_mode(&mut render_state.surface, options.present_mode);
+            }
+            ensure_renderer(
+                &mut self.renderers,
+                &self.context,
+                &render_state.surface,
+                &options,
+            )?;
+            state.schedule_redraw();
+        }
+        Ok(())
+    }
+
+    /// Creates a surface for the window and the renderer for its device if there is none, `size`
+    /// is in physical pixels
+    fn create_surface(&mut self, window: Arc<W>, size: (u32, u32)) -> Result<RenderSurface<'s>> {
+        let surface_future =
+            self.context
+                .create_surface(window, size.0, size.1, self.options.present_mode);
+        let surface = pollster::block_on(surface_future).context("couldn't create surface")?;
+
+        let device_lost = self.device_lost.clone();
+        self.context.devices[surface.dev_id]
+            .device
+            .set_device_lost_callback(move |reason, _message| {
+                // Other reasons come from dropping the device or replacing this callback
+                if let wgpu::DeviceLostReason::Unknown = reason {
+                    device_lost.store(true, Ordering::Relaxed);
+                }
+            });
+
+        ensure_renderer(&mut self.renderers, &self.context, &surface, &self.options)?;
+        Ok(surface)
+    }
+
+    /// Drops every surface, renderer and device, then creates them again for active windows
+    fn recover_from_device_loss(&mut self) -> Result<()> {
+        self.device_lost.store(false, Ordering::Relaxed);
+
+        let mut active = Vec::new();
+        for (id, state) in &mut self.windows {
+            let render_state = std::mem::replace(&mut state.render_state, RenderState::Suspended);
+            if let RenderState::Active(render_state) = render_state {
+                active.push((*id, render_state.window, state.ctx.physical_size()));
+            }
+        }
+        self.renderers.clear();
+        self.context = RenderContext::new();
+
+        for (id, window, size) in active {
+            let surface = self.create_surface(window.clone(), size)?;
+            if let Some(state) = self.windows.get_mut(&id) {
+                state.render_state =
+                    RenderState::Active(Box::new(ActiveRenderState { window, surface }));
+                state.schedule_redraw();
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws the window. Frames requested by the app are skipped if the view didn't change since
+    /// the last one
+    fn redraw(&mut self, id: WindowId) -> AppResponce {
+        let Some(state) = self.windows.get_mut(&id) else {
+            return AppResponce::Handled;
+        };
+        if state.animating {
+            state.animating = false;
+            let time = (self.clock)();
+            state.send_view_event(&ViewEvent::AnimationFrame { time });
+        }
+        if let RenderState::Suspended = state.render_state {
+            return AppResponce::Handled;
+        }
+        let scheduled = std::mem::take(&mut state.redraw_scheduled);
+        // The last presented frame is still correct, unless the overlay has new timings to show
+        let overlay = self.options.profiling && self.options.profiling_overlay;
+        if scheduled && !state.dirty && !overlay {
+            return AppResponce::Handled;
+        }
+        state.dirty = false;
+        let RenderState::Active(render_state) = &mut state.render_state else {
+            return AppResponce::Handled;
+        };
+
+        // Empty the scene of objects to draw. You could create a new Scene each time, but in this case
+        // the same Scene is reused so that the underlying memory allocation can also be reused.
+        self.scene.reset();
+        self.fragment.reset();
+        self.ui_scene.reset();
+
+        let frame_time = (self.clock)();
+        let frame_start = Instant::now();
+
+        // Views draw in logical pixels, the surface is in physical ones
+        state.root_view.render(&mut self.ui_scene);
+        let view_render = frame_start.elapsed();
+        translate_scene(&self.ui_scene, &mut self.fragment);
+        let transform = Affine::scale(state.ctx.scale);
+        self.scene.append(&self.fragment, Some(transform));
+        if overlay {
+            self.profiler.draw_overlay(id, &mut self.scene, transform);
+        }
+        let encoding = frame_start.elapsed() - view_render;
+
+        // Get the window size
+        let width = render_state.surface.config.width;
+        let height = render_state.surface.config.height;
+
+        // Get the surface's texture
+        let surface_texture = match render_state.surface.surface.get_current_texture() {
+            Ok(texture) => texture,
+            // The frame is skipped, the next one may succeed
+            Err(wgpu::SurfaceError::Timeout) => {
+                state.schedule_redraw();
+                return AppResponce::Handled;
+            }
+            // Surface doesn't match the window anymore, reconfiguring brings it back
+            Err(wgpu::SurfaceError::Outdated | wgpu::SurfaceError::Lost) => {
+                self.context
+                    .resize_surface(&mut render_state.surface, width, height);
+                state.schedule_redraw();
+                return AppResponce::Handled;
+            }
+            Err(err) => {
+                return AppResponce::Error(format!("couldn't get surface texture: {err}").into())
+            }
+        };
+
+        // Get the RenderSurface (surface + config)
+        let surface = &render_state.surface;
+
+        // Get a handle to the device
+        let device_handle = &self.context.devices[surface.dev_id];
+
+        let Some(renderer) = self
+            .renderers
+            .get_mut(surface.dev_id)
+            .and_then(Option::as_mut)
+        else {
+            return AppResponce::Error("no renderer for the surface's device".into());
+        };
+
+        // Render to the surface's texture
+        let result = renderer.render_to_surface(
+            &device_handle.device,
+            &device_handle.queue,
+            &self.scene,
+            &surface_texture,
+            &vello::RenderParams {
+                base_color: self.options.base_color,
+                width,
+                height,
+                antialiasing_method: self.options.antialiasing,
+            },
+        );
+        if let Err(err) = result {
+            return AppResponce::Error(format!("couldn't render to surface: {err}").into());
+        }
+
+        // Queue the texture to be presented on the surface
+        surface_texture.present();
+
+        device_handle.device.poll(wgpu::Maintain::Poll);
+
+        if self.options.profiling {
+            self.profiler.push(FrameTimings {
+                window: id,
+                start: frame_time,
+                view_render,
+                encoding,
+                submit: frame_start.elapsed() - view_render - encoding,
+                gpu: None,
+            });
+            #[cfg(feature = "wgpu-profiler")]
+            {
+                if let Some(gpu) = crate::profiling::take_gpu_time(renderer) {
+                    self.profiler.set_gpu_time(id, gpu);
+                }
+            }
+        }
+
+        AppResponce::Handled
+    }
+}
+
+impl<'s, W: WindowHandler + 'static, SW: SubwindowHandler, V: RootView> AppHandler<W, SW>
+    for VelloApp<'s, W, V>
+{
+    fn handle_window_event(&mut self, window: WindowId, event: WindowEvent) -> AppResponce {
+        if let Err(err) = self.sync() {
+            return AppResponce::Error(err.into());
+        }
+
+        let Some(state) = self.windows.get_mut(&window) else {
+            return AppResponce::Handled;
+        };
+
+        match event {
+            WindowEvent::CloseRequested => {
+                match state.send_view_event(&ViewEvent::CloseRequested) {
+                    ViewResponce::Skipped => {
+                        // Surface must be dropped before the window gets destroyed
+                        self.windows.remove(&window);
+                        self.options_handle.remove_window(window);
+                        AppResponce::CloseWindow(window)
+                    }
+                    // Views may draw a confirmation instead, so any other responce keeps it open
+                    _ => AppResponce::Handled,
+                }
+            }
+            WindowEvent::Redraw => self.redraw(window),
+            event => {
+                let resized = matches!(
+                    event,
+                    WindowEvent::Resize { .. } | WindowEvent::ScaleFactorChanged { .. }
+                );
+                // Only drawing needs a surface, input reaches the view even while suspended
+                if let Some(event) = window_to_view_event(event, &mut state.ctx) {
+                    state.send_view_event(&event);
+                }
+                if resized {
+                    if let RenderState::Active(render_state) = &mut state.render_state {
+                        let (width, height) = state.ctx.physical_size();
+                        self.context
+                            .resize_surface(&mut render_state.surface, width, height);
+                        state.schedule_redraw();
+                    }
+                }
+                AppResponce::Handled
+            }
+        }
+    }
+
+    fn handle_window_update(&mut self, id: WindowId, window: W) -> AppResponce {
+        if let Err(err) = self.sync() {
+            return AppResponce::Error(err.into());
+        }
+
+        let make_view = &mut self.make_view;
+        let state = self.windows.entry(id).or_insert_with(|| WindowState {
+            render_state: RenderState::Suspended,
+            window: None,
+            ctx: Default::default(),
+            root_view: make_view(id),
+            dirty: true,
+            redraw_scheduled: false,
+            animating: false,
+            ime_area: None,
+        });
+
+        // Window handle may be new after resume, so the old surface can't be reused
+        state.render_state = RenderState::Suspended;
+
+        let window = Arc::new(window);
+        state.window = Some(window.clone());
+        let weak = Arc::downgrade(&window);
+        self.options_handle.add_window(id, move || {
+            if let Some(window) = weak.upgrade() {
+                window.request_redraw();
+            }
+        });
+
+        window.request_redraw();
+        // IME stays disabled until the view reports an area for composed text
+        window.set_ime_allowed(false);
+        state.ime_area = None;
+        state.update_ime();
+
+        let params = window.get_params();
+        state.ctx.size = params.size;
+        state.ctx.scale = params.scale;
+        let event = state.ctx.resize_event();
+        state.send_view_event(&event);
+        let size = state.ctx.physical_size();
+
+        // Windows without a display, like mock ones, can't have a surface and stay suspended
+        if matches!(window.window_handle(), Err(HandleError::Unavailable))
+            || matches!(window.display_handle(), Err(HandleError::Unavailable))
+        {
+            return AppResponce::Handled;
+        }
+
+        let surface = match self.create_surface(window.clone(), size) {
+            Ok(surface) => surface,
+            // The window stays suspended
+            Err(err) => return AppResponce::Error(err.into()),
+        };
+
+        // Save the Window and Surface to a state variable, the new surface has no frame yet
+        if let Some(state) = self.windows.get_mut(&id) {
+            state.dirty = true;
+            state.render_state =
+                RenderState::Active(Box::new(ActiveRenderState { window, surface }));
+        }
+
+        AppResponce::Handled
+    }
+
+    fn handle_subwindow_update(&mut self, _id: WindowId, _window: SW) -> AppResponce {
+        AppResponce::Handled
+    }
+}
+
+/// Creates an app that renders every window with its own root view, `make_view` is called once
+/// for every new window
+pub fn make_vello_app<'s, W: WindowHandler + 's, V: RootView>(
+    make_view: impl FnMut(WindowId) -> V + Send + 'static,
+) -> VelloApp<'s, W, V> {
+    make_vello_app_with_options(VelloOptions::default(), make_view)
+}
+
+pub fn make_vello_app_with_options<'s, W: WindowHandler + 's, V: RootView>(
+    options: VelloOptions,
+    make_view: impl FnMut(WindowId) -> V + Send + 'static,
+) -> VelloApp<'s, W, V> {
+    VelloApp {
+        context: RenderContext::new(),
+        renderers: Vec::new(),
+        windows: HashMap::new(),
+        scene: Scene::new(),
+        fragment: Scene::new(),
+        ui_scene: kreuz_ui::Scene::new(),
+        make_view: Box::new(make_view),
+        options,
+        options_handle: VelloOptionsHandle::new(options),
+        device_lost: Arc::new(AtomicBool::new(false)),
+        clock: {
+            let start = Instant::now();
+            Box::new(move || start.elapsed())
+        },
+        profiler: FrameProfiler::default(),
+    }
+}
+
+/// Windows on the same device share the renderer, it's created with the first of them
+fn ensure_renderer(
+    renderers: &mut Vec<Option<Renderer>>,
+    render_cx: &RenderContext,
+    surface: &RenderSurface,
+    options: &VelloOptions,
+) -> Result<()> {
+    if renderers.len() < render_cx.devices.len() {
+        renderers.resize_with(render_cx.devices.len(), || None);
+    }
+    if renderers[surface.dev_id].is_none() {
+        renderers[surface.dev_id] = Some(create_vello_renderer(render_cx, surface, options)?);
+    }
+    Ok(())
+}
+
+/// Helper function that creates a vello `Renderer` for a given `RenderContext` and `RenderSurface`
+fn create_vello_renderer(
+    render_cx: &RenderContext,
+    surface: &RenderSurface,
+    options: &VelloOptions,
+) -> Result<Renderer> {
+    Renderer::new(
+        &render_cx.devices[surface.dev_id].device,
+        RendererOptions {
+            surface_format: Some(surface.format),
+            use_cpu: options.use_cpu,
+            // Every method is supported, so that antialiasing can be changed at runtime
+            antialiasing_support: vello::AaSupport::all(),
+            num_init_threads: options.num_init_threads,
+        },
+    )
+    .map_err(|err| anyhow!("couldn't create renderer: {err}"))
+}
@@ -0,0 +1,162 @@
+use kreuz_ui::ViewEvent;
+use kreuz_window::{ImeEvent, MouseButton, ScrollDelta, Theme, TouchPhase, WindowEvent};
+use peniko::kurbo::{Point, Size};
+use std::collections::HashMap;
+
+use super::keyboard;
+
+pub(crate) struct Context {
+    /// Logical size of the window surface
+    pub size: Size,
+    pub scale: f64,
+    cursor_pos: Option<Point>,
+    pressed_mb: HashMap<kreuz_ui::MouseButton, bool>,
+    modifiers: kreuz_ui::Modifiers,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Self {
+            size: Size::ZERO,
+            scale: 1.,
+            cursor_pos: None,
+            pressed_mb: HashMap::new(),
+            modifiers: Default::default(),
+        }
+    }
+}
+
+impl Context {
+    /// Size of the surface texture
+    pub fn physical_size(&self) -> (u32, u32) {
+        let size = (self.size * self.scale).round();
+        (size.width as u32, size.height as u32)
+    }
+
+    pub fn resize_event(&self) -> ViewEvent {
+        ViewEvent::Resize {
+            size: self.size,
+            scale: self.scale,
+        }
+    }
+}
+
+pub(crate) fn window_to_view_event(event: WindowEvent, ctx: &mut Context) -> Option<ViewEvent> {
+    match event {
+        WindowEvent::Resize { new_size } => {
+            ctx.size = new_size;
+            Some(ctx.resize_event())
+        }
+        WindowEvent::Redraw => None,
+        WindowEvent::CloseRequested => Some(ViewEvent::CloseRequested),
+        WindowEvent::Moved { .. } => None,
+        WindowEvent::Focused { focused } => Some(ViewEvent::Focused { focused }),
+        WindowEvent::Occluded { .. } => None,
+        WindowEvent::ScaleFactorChanged { scale } => {
+            ctx.scale = scale;
+            Some(ctx.resize_event())
+        }
+        WindowEvent::ThemeChanged { theme } => Some(ViewEvent::ThemeChanged {
+            theme: match theme {
+                Theme::Light => kreuz_ui::Theme::Light,
+                Theme::Dark => kreuz_ui::Theme::Dark,
+            },
+        }),
+        WindowEvent::CursorEntered => Some(ViewEvent::CursorEntered),
+        WindowEvent::CursorLeft => Some(ViewEvent::CursorLeft),
+        WindowEvent::CursorMove { pos } => {
+            ctx.cursor_pos = Some(pos);
+            Some(ViewEvent::CursorMove { pos })
+        }
+        WindowEvent::MouseButton { button, state } => {
+            let button = match button {
+                MouseButton::Left => kreuz_ui::MouseButton::Left,
+                MouseButton::Right => kreuz_ui::MouseButton::Right,
+                MouseButton::Middle => kreuz_ui::MouseButton::Middle,
+                MouseButton::Back => kreuz_ui::MouseButton::Back,
+                MouseButton::Forward => kreuz_ui::MouseButton::Forward,
+            };
+            let pos = ctx.cursor_pos.unwrap_or_default();
+            Some(match state {
+                kreuz_window::ButtonState::Pressed => {
+                    ctx.pressed_mb.insert(button, true);
+                    ViewEvent::MouseButtonPress { pos, button }
+                }
+                kreuz_window::ButtonState::Released => {
+                    ctx.pressed_mb.remove(&button);
+                    ViewEvent::MouseButtonRelease { pos, button }
+                }
+            })
+        }
+        WindowEvent::MouseWheel { delta, phase } => {
+            let pos = ctx.cursor_pos.unwrap_or_default();
+            let delta = match delta {
+                ScrollDelta::Lines(delta) => kreuz_ui::ScrollDelta::Lines(delta),
+                ScrollDelta::Pixels(delta) => kreuz_ui::ScrollDelta::Pixels(delta),
+            };
+            Some(ViewEvent::MouseWheel {
+                pos,
+                delta,
+                phase: convert_touch_phase(phase),
+            })
+        }
+        WindowEvent::Touch {
+            id,
+            pos,
+            phase,
+            force,
+        } => Some(ViewEvent::Touch {
+            id,
+            pos,
+            phase: convert_touch_phase(phase),
+            force,
+        }),
+        WindowEvent::PinchGesture { delta, phase } => Some(ViewEvent::PinchGesture {
+            pos: ctx.cursor_pos.unwrap_or_default(),
+            delta,
+            phase: convert_touch_phase(phase),
+        }),
+        WindowEvent::PanGesture { delta, phase } => Some(ViewEvent::PanGesture {
+            pos: ctx.cursor_pos.unwrap_or_default(),
+            delta,
+            phase: convert_touch_phase(phase),
+        }),
+        WindowEvent::RotationGesture { delta, phase } => Some(ViewEvent::RotationGesture {
+            pos: ctx.cursor_pos.unwrap_or_default(),
+            delta,
+            phase: convert_touch_phase(phase),
+        }),
+        WindowEvent::DoubleTapGesture => Some(ViewEvent::DoubleTapGesture {
+            pos: ctx.cursor_pos.unwrap_or_default(),
+        }),
+        WindowEvent::KeyDown { event } => Some(ViewEvent::KeyDown {
+            event: keyboard::convert_key_event(event, ctx.modifiers),
+        }),
+        WindowEvent::KeyUp { event } => Some(ViewEvent::KeyUp {
+            event: keyboard::convert_key_event(event, ctx.modifiers),
+        }),
+        WindowEvent::ModifiersChanged { modifiers } => {
+            ctx.modifiers = keyboard::convert_modifiers(modifiers);
+            Some(ViewEvent::ModifiersChanged {
+                modifiers: ctx.modifiers,
+            })
+        }
+        WindowEvent::Ime { event } => Some(ViewEvent::Ime {
+            event: match event {
+                ImeEvent::Enabled => kreuz_ui::ImeEvent::Enabled,
+                ImeEvent::Preedit { text, cursor } => kreuz_ui::ImeEvent::Preedit { text, cursor },
+                ImeEvent::Commit { text } => kreuz_ui::ImeEvent::Commit { text },
+                ImeEvent::Disabled => kreuz_ui::ImeEvent::Disabled,
+            },
+        }),
+    }
+}
+
+fn convert_touch_phase(phase: TouchPhase) -> kreuz_ui::TouchPhase {
+    match phase {
+        TouchPhase::Started => kreuz_ui::TouchPhase::Started,
+        TouchPhase::Moved => kreuz_ui::TouchPhase::Moved,
+        TouchPhase::Ended => kreuz_ui::TouchPhase::Ended,
+        TouchPhase::Cancelled => kreuz_ui::TouchPhase::Cancelled,
+    }
+}
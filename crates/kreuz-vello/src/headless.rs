@@ -0,0 +1,171 @@
+use anyhow::{anyhow, bail, Result};
+use kreuz_ui::{RootView, ViewEvent};
+use std::{num::NonZeroUsize, sync::mpsc, sync::Arc};
+use vello::{
+    kurbo::{Affine, Size},
+    peniko::{Blob, Color, Format, Image},
+    wgpu, AaConfig, RenderParams, Renderer, RendererOptions, Scene,
+};
+
+use super::scene::translate_scene;
+
+/// Renders root views into images without a window, for tests and CI machines without a display
+pub struct HeadlessRenderer {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    renderer: Renderer,
+    scene: Scene,
+    /// Unscaled view content, appended to `scene` with the scale applied
+    fragment: Scene,
+    ui_scene: kreuz_ui::Scene,
+}
+
+impl HeadlessRenderer {
+    /// Picks the default adapter, falling back to a software one when there is no GPU.
+    /// Vello's CPU path is used on software adapters
+    pub fn new() -> Result<Self> {
+        let instance = wgpu::Instance::default();
+        let adapter = [false, true]
+            .into_iter()
+            .find_map(|force_fallback_adapter| {
+                pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+                    force_fallback_adapter,
+                    ..Default::default()
+                }))
+            })
+            .ok_or_else(|| anyhow!("no suitable wgpu adapter found"))?;
+        let (device, queue) = pollster::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("kreuz headless device"),
+                required_features: adapter.features() & wgpu::Features::CLEAR_TEXTURE,
+                required_limits: wgpu::Limits::default(),
+                memory_hints: wgpu::MemoryHints::default(),
+            },
+            None,
+        ))?;
+        let renderer = Renderer::new(
+            &device,
+            RendererOptions {
+                surface_format: None,
+                use_cpu: adapter.get_info().device_type == wgpu::DeviceType::Cpu,
+                antialiasing_support: vello::AaSupport::all(),
+                num_init_threads: NonZeroUsize::new(1),
+            },
+        )
+        .map_err(|err| anyhow!("couldn't create renderer: {err}"))?;
+        Ok(Self {
+            device,
+            queue,
+            renderer,
+            scene: Scene::new(),
+            fragment: Scene::new(),
+            ui_scene: kreuz_ui::Scene::new(),
+        })
+    }
+
+    #[cfg(all(test, feature = "wgpu-profiler"))]
+    pub(crate) fn renderer_mut(&mut self) -> &mut Renderer {
+        &mut self.renderer
+    }
+
+    /// Renders the view into an RGBA image, `size` is in logical pixels and the image is `scale`
+    /// times bigger. The view gets `ViewEvent::Resize` first, like it would in a window
+    pub fn render(&mut self, view: &mut impl RootView, size: Size, scale: f64) -> Result<Image> {
+        let width = (size.width * scale).round() as u32;
+        let height = (size.height * scale).round() as u32;
+        if width == 0 || height == 0 {
+            bail!("can't render an empty image of size {width}x{height}");
+        }
+
+        view.handle_event(&ViewEvent::Resize { size, scale });
+
+        self.scene.reset();
+        self.fragment.reset();
+        self.ui_scene.reset();
+        view.render(&mut self.ui_scene);
+        translate_scene(&self.ui_scene, &mut self.fragment);
+        self.scene
+            .append(&self.fragment, Some(Affine::scale(scale)));
+
+        let extent = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("kreuz headless target"),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        self.renderer
+            .render_to_texture(
+                &self.device,
+                &self.queue,
+                &self.scene,
+                &view,
+                &RenderParams {
+                    base_color: Color::BLACK,
+                    width,
+                    height,
+                    antialiasing_method: AaConfig::Msaa16,
+                },
+            )
+            .map_err(|err| anyhow!("failed to render: {err}"))?;
+
+        // Rows of a copied texture must be aligned
+        let padded_row = (width * 4).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT);
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("kreuz headless readback"),
+            size: padded_row as u64 * height as u64,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("kreuz headless copy"),
+            });
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row),
+                    rows_per_image: None,
+                },
+            },
+            extent,
+        );
+        self.queue.submit([encoder.finish()]);
+
+        let slice = buffer.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver.recv()??;
+
+        let mapped = slice.get_mapped_range();
+        let mut data = Vec::with_capacity((width * height * 4) as usize);
+        for row in mapped.chunks_exact(padded_row as usize) {
+            data.extend_from_slice(&row[..(width * 4) as usize]);
+        }
+        drop(mapped);
+        buffer.unmap();
+
+        Ok(Image::new(
+            Blob::new(Arc::new(data)),
+            Format::Rgba8,
+            width,
+            height,
+        ))
+    }
+}
@@ -0,0 +1,86 @@
+use kreuz_ui::{KeyCode as UiKeyCode, NamedKey as UiNamedKey};
+use kreuz_window::{Key, KeyCode, KeyEvent, KeyLocation, Modifiers, NamedKey};
+
+macro_rules! map_variants {
+    ($value:expr, $from:ident => $to:ident, [$($variant:ident),* $(,)?]) => {
+        match $value {
+            $($from::$variant => $to::$variant,)*
+        }
+    };
+}
+
+pub(crate) fn convert_key_event(
+    event: KeyEvent,
+    modifiers: kreuz_ui::Modifiers,
+) -> kreuz_ui::KeyEvent {
+    kreuz_ui::KeyEvent {
+        key: convert_key(event.key),
+        code: convert_key_code(event.code),
+        location: convert_key_location(event.location),
+        repeat: event.repeat,
+        text: event.text,
+        modifiers,
+    }
+}
+
+pub(crate) fn convert_modifiers(modifiers: Modifiers) -> kreuz_ui::Modifiers {
+    kreuz_ui::Modifiers {
+        shift: modifiers.shift,
+        control: modifiers.control,
+        alt: modifiers.alt,
+        super_key: modifiers.super_key,
+    }
+}
+
+fn convert_key(key: Key) -> kreuz_ui::Key {
+    match key {
+        Key::Named(key) => kreuz_ui::Key::Named(convert_named_key(key)),
+        Key::Character(ch) => kreuz_ui::Key::Character(ch),
+        Key::Dead(ch) => kreuz_ui::Key::Dead(ch),
+        Key::Unidentified => kreuz_ui::Key::Unidentified,
+    }
+}
+
+fn convert_key_location(location: KeyLocation) -> kreuz_ui::KeyLocation {
+    match location {
+        KeyLocation::Standard => kreuz_ui::KeyLocation::Standard,
+        KeyLocation::Left => kreuz_ui::KeyLocation::Left,
+        KeyLocation::Right => kreuz_ui::KeyLocation::Right,
+        KeyLocation::Numpad => kreuz_ui::KeyLocation::Numpad,
+    }
+}
+
+fn convert_named_key(key: NamedKey) -> kreuz_ui::NamedKey {
+    map_variants!(key, NamedKey => UiNamedKey, [
+        Alt, AltGraph, CapsLock, Control, Fn, NumLock, ScrollLock, Shift, Super,
+        Enter, Tab, Space,
+        ArrowDown, ArrowLeft, ArrowRight, ArrowUp, End, Home, PageDown, PageUp,
+        Backspace, Clear, Copy, Cut, Delete, Insert, Paste, Redo, Undo,
+        ContextMenu, Escape, Find, Help, Pause, PrintScreen,
+        MediaPlayPause, MediaStop, MediaTrackNext, MediaTrackPrevious,
+        AudioVolumeDown, AudioVolumeUp, AudioVolumeMute,
+        F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+        F13, F14, F15, F16, F17, F18, F19, F20, F21, F22, F23, F24,
+    ])
+}
+
+fn convert_key_code(code: KeyCode) -> kreuz_ui::KeyCode {
+    map_variants!(code, KeyCode => UiKeyCode, [
+        Backquote, Backslash, BracketLeft, BracketRight, Comma,
+        Digit0, Digit1, Digit2, Digit3, Digit4, Digit5, Digit6, Digit7, Digit8, Digit9,
+        Equal, IntlBackslash,
+        KeyA, KeyB, KeyC, KeyD, KeyE, KeyF, KeyG, KeyH, KeyI, KeyJ, KeyK, KeyL, KeyM,
+        KeyN, KeyO, KeyP, KeyQ, KeyR, KeyS, KeyT, KeyU, KeyV, KeyW, KeyX, KeyY, KeyZ,
+        Minus, Period, Quote, Semicolon, Slash,
+        AltLeft, AltRight, Backspace, CapsLock, ContextMenu, ControlLeft, ControlRight, Enter,
+        SuperLeft, SuperRight, ShiftLeft, ShiftRight, Space, Tab,
+        Delete, End, Home, Insert, PageDown, PageUp, ArrowDown, ArrowLeft, ArrowRight, ArrowUp,
+        NumLock, Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7,
+        Numpad8, Numpad9, NumpadAdd, NumpadDecimal, NumpadDivide, NumpadEnter, NumpadMultiply,
+        NumpadSubtract,
+        Escape, PrintScreen, ScrollLock, Pause,
+        F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+        F13, F14, F15, F16, F17, F18, F19, F20, F21, F22, F23, F24,
+        Unidentified,
+    ])
+}
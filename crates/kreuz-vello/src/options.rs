@@ -0,0 +1,97 @@
+use kreuz_window::WindowId;
+use std::{
+    collections::HashMap,
+    fmt,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
+use vello::{peniko::Color, wgpu::PresentMode, AaConfig};
+
+/// Settings of the vello renderer, defaults favour quality over latency
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct VelloOptions {
+    pub antialiasing: AaConfig,
+    /// Color the surface is cleared with before drawing
+    pub base_color: Color,
+    /// `AutoVsync` saves battery, `Mailbox` and `Immediate` lower latency where supported
+    pub present_mode: PresentMode,
+    /// Runs shaders on CPU, for devices without working compute shaders
+    pub use_cpu: bool,
+    /// Threads used to compile shaders at startup, `None` uses all available threads
+    pub num_init_threads: Option<NonZeroUsize>,
+    /// Collects timings of every frame, see `VelloApp::profiler`
+    pub profiling: bool,
+    /// Draws a graph of recent frame times over the view, needs `profiling`
+    pub profiling_overlay: bool,
+}
+
+impl Default for VelloOptions {
+    fn default() -> Self {
+        Self {
+            antialiasing: AaConfig::Msaa16,
+            base_color: Color::BLACK,
+            present_mode: PresentMode::AutoVsync,
+            use_cpu: false,
+            num_init_threads: NonZeroUsize::new(1),
+            profiling: false,
+            profiling_overlay: false,
+        }
+    }
+}
+
+/// Changes options of a running app. Every window is asked for a redraw on change, the options
+/// are applied before the app handles it
+#[derive(Clone)]
+pub struct VelloOptionsHandle(Arc<Mutex<SharedOptions>>);
+
+struct SharedOptions {
+    options: VelloOptions,
+    /// Redraw requests of open windows
+    windows: HashMap<WindowId, Box<dyn Fn() + Send>>,
+}
+
+impl VelloOptionsHandle {
+    pub(crate) fn new(options: VelloOptions) -> Self {
+        Self(Arc::new(Mutex::new(SharedOptions {
+            options,
+            windows: HashMap::new(),
+        })))
+    }
+
+    pub fn get(&self) -> VelloOptions {
+        self.0.lock().unwrap().options
+    }
+
+    pub fn set(&self, options: VelloOptions) {
+        self.update(|current| *current = options);
+    }
+
+    /// Changes options in place, e.g. `handle.update(|options| options.use_cpu = true)`
+    pub fn update(&self, f: impl FnOnce(&mut VelloOptions)) {
+        let mut shared = self.0.lock().unwrap();
+        let old = shared.options;
+        f(&mut shared.options);
+        if shared.options != old {
+            for request_redraw in shared.windows.values() {
+                request_redraw();
+            }
+        }
+    }
+
+    pub(crate) fn add_window(&self, id: WindowId, request_redraw: impl Fn() + Send + 'static) {
+        let mut shared = self.0.lock().unwrap();
+        shared.windows.insert(id, Box::new(request_redraw));
+    }
+
+    pub(crate) fn remove_window(&self, id: WindowId) {
+        self.0.lock().unwrap().windows.remove(&id);
+    }
+}
+
+impl fmt::Debug for VelloOptionsHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VelloOptionsHandle")
+            .field(&self.get())
+            .finish()
+    }
+}
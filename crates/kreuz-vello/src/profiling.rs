@@ -0,0 +1,193 @@
+use kreuz_window::WindowId;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use vello::{
+    kurbo::{Affine, Rect},
+    peniko::{Color, Fill},
+    Scene,
+};
+
+/// Frames kept by the profiler, a few seconds at common refresh rates
+const HISTORY: usize = 240;
+
+/// Frame time that fits into a 60 Hz refresh
+const FRAME_BUDGET: Duration = Duration::from_micros(16_667);
+
+/// Timings of a single drawn frame
+#[derive(Clone, Debug)]
+pub struct FrameTimings {
+    pub window: WindowId,
+    /// When drawing started, counted from the start of the app
+    pub start: Duration,
+    /// `RootView::render`
+    pub view_render: Duration,
+    /// Translating the view scene into a vello one
+    pub encoding: Duration,
+    /// Acquiring the surface texture, `render_to_surface` and presenting
+    pub submit: Duration,
+    /// Time the GPU spent on vello passes, reported a few frames late as queries finish.
+    /// Needs the `wgpu-profiler` feature and a device supporting timestamp queries
+    pub gpu: Option<Duration>,
+}
+
+impl FrameTimings {
+    /// CPU time of the frame
+    pub fn total(&self) -> Duration {
+        self.view_render + self.encoding + self.submit
+    }
+}
+
+/// GPU time of the last frame vello finished profiling.
+/// `render_to_surface` already processes the finished frame and keeps the result in
+/// `Renderer::profile_result`, which is `doc(hidden)` in vello 0.3, so check it when bumping vello
+#[cfg(feature = "wgpu-profiler")]
+pub(crate) fn take_gpu_time(renderer: &mut vello::Renderer) -> Option<Duration> {
+    renderer
+        .profile_result
+        .take()
+        .map(|results| gpu_time(&results))
+}
+
+/// Sums the top level scopes, nested ones are already part of them
+#[cfg(feature = "wgpu-profiler")]
+fn gpu_time(results: &[wgpu_profiler::GpuTimerQueryResult]) -> Duration {
+    let seconds = results
+        .iter()
+        .filter_map(|result| result.time.as_ref())
+        .map(|time| time.end - time.start)
+        .sum::<f64>();
+    Duration::from_secs_f64(seconds)
+}
+
+/// Timings of recent frames of all windows, shared with the app while profiling is enabled
+#[derive(Clone, Debug, Default)]
+pub struct FrameProfiler(Arc<Mutex<VecDeque<FrameTimings>>>);
+
+impl FrameProfiler {
+    /// Recent frames, the oldest first
+    pub fn frames(&self) -> Vec<FrameTimings> {
+        self.0.lock().unwrap().iter().cloned().collect()
+    }
+
+    pub fn last(&self) -> Option<FrameTimings> {
+        self.0.lock().unwrap().back().cloned()
+    }
+
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+
+    pub(crate) fn push(&self, timings: FrameTimings) {
+        let mut frames = self.0.lock().unwrap();
+        if frames.len() == HISTORY {
+            frames.pop_front();
+        }
+        frames.push_back(timings);
+    }
+
+    /// Attaches GPU time to the latest frame of the window that has none yet
+    #[cfg_attr(not(feature = "wgpu-profiler"), allow(dead_code))]
+    pub(crate) fn set_gpu_time(&self, window: WindowId, time: Duration) {
+        let mut frames = self.0.lock().unwrap();
+        let frame = frames
+            .iter_mut()
+            .rev()
+            .find(|frame| frame.window == window && frame.gpu.is_none());
+        if let Some(frame) = frame {
+            frame.gpu = Some(time);
+        }
+    }
+
+    /// Draws a bar per recent frame of the window, `transform` maps logical pixels to the scene
+    pub(crate) fn draw_overlay(&self, window: WindowId, scene: &mut Scene, transform: Affine) {
+        const WIDTH: f64 = 240.;
+        const HEIGHT: f64 = 60.;
+        const MARGIN: f64 = 8.;
+        // Bars are scaled so that two frame budgets fill the graph
+        let scale = HEIGHT / (FRAME_BUDGET.as_secs_f64() * 2.);
+
+        let frames = self.0.lock().unwrap();
+        let background = Rect::from_origin_size((MARGIN, MARGIN), (WIDTH, HEIGHT));
+        scene.fill(
+            Fill::NonZero,
+            transform,
+            Color::rgba8(0, 0, 0, 0xa0),
+            None,
+            &background,
+        );
+
+        let bars = frames
+            .iter()
+            .filter(|frame| frame.window == window)
+            .rev()
+            .take(WIDTH as usize / 2);
+        for (index, frame) in bars.enumerate() {
+            let time = frame.gpu.unwrap_or_default().max(frame.total());
+            let height = (time.as_secs_f64() * scale).min(HEIGHT);
+            let x = background.x1 - (index + 1) as f64 * 2.;
+            let color = if time > FRAME_BUDGET {
+                Color::rgb8(0xe0, 0x40, 0x40)
+            } else {
+                Color::rgb8(0x40, 0xc0, 0x60)
+            };
+            let bar = Rect::new(x, background.y1 - height, x + 1.5, background.y1);
+            scene.fill(Fill::NonZero, transform, color, None, &bar);
+        }
+
+        let budget_y = background.y1 - FRAME_BUDGET.as_secs_f64() * scale;
+        let budget = Rect::new(background.x0, budget_y, background.x1, budget_y + 1.);
+        scene.fill(
+            Fill::NonZero,
+            transform,
+            Color::rgba8(0xff, 0xff, 0xff, 0x80),
+            None,
+            &budget,
+        );
+    }
+}
+
+#[cfg(all(test, feature = "wgpu-profiler"))]
+mod tests {
+    use super::*;
+    use wgpu_profiler::GpuTimerQueryResult;
+
+    fn scope(
+        time: Option<std::ops::Range<f64>>,
+        nested: Vec<GpuTimerQueryResult>,
+    ) -> GpuTimerQueryResult {
+        GpuTimerQueryResult {
+            label: "scope".into(),
+            pid: std::process::id(),
+            tid: std::thread::current().id(),
+            time,
+            nested_queries: nested,
+        }
+    }
+
+    #[test]
+    fn gpu_time_sums_top_level_scopes() {
+        let results = [
+            scope(Some(1.0..1.5), vec![scope(Some(1.0..1.25), vec![])]),
+            scope(None, vec![]),
+            scope(Some(2.0..2.25), vec![]),
+        ];
+        assert_eq!(gpu_time(&results), Duration::from_millis(750));
+    }
+
+    #[test]
+    fn gpu_time_is_taken_from_renderer() {
+        // Machines without any adapter can't create a renderer
+        let Ok(mut headless) = crate::HeadlessRenderer::new() else {
+            return;
+        };
+        let renderer = headless.renderer_mut();
+        assert_eq!(take_gpu_time(renderer), None);
+
+        renderer.profile_result = Some(vec![scope(Some(0.0..0.5), vec![])]);
+        assert_eq!(take_gpu_time(renderer), Some(Duration::from_millis(500)));
+        assert!(renderer.profile_result.is_none());
+    }
+}
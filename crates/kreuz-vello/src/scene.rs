@@ -0,0 +1,55 @@
+use kreuz_ui::SceneCommand;
+use vello::{skrifa::instance::NormalizedCoord, Glyph, Scene};
+
+/// Replays commands recorded by the UI into the vello scene
+pub(crate) fn translate_scene(source: &kreuz_ui::Scene, target: &mut Scene) {
+    for command in source.commands() {
+        match command {
+            SceneCommand::Fill {
+                style,
+                transform,
+                brush,
+                brush_transform,
+                shape,
+            } => target.fill(*style, *transform, brush, *brush_transform, shape),
+            SceneCommand::Stroke {
+                style,
+                transform,
+                brush,
+                brush_transform,
+                shape,
+            } => target.stroke(style, *transform, brush, *brush_transform, shape),
+            SceneCommand::PushLayer {
+                blend,
+                alpha,
+                transform,
+                clip,
+            } => target.push_layer(*blend, *alpha, *transform, clip),
+            SceneCommand::PopLayer => target.pop_layer(),
+            SceneCommand::Image { image, transform } => target.draw_image(image, *transform),
+            SceneCommand::Glyphs(run) => {
+                let coords = run
+                    .normalized_coords
+                    .iter()
+                    .map(|coord| NormalizedCoord::from_bits(*coord))
+                    .collect::<Vec<_>>();
+                target
+                    .draw_glyphs(&run.font)
+                    .font_size(run.font_size)
+                    .transform(run.transform)
+                    .glyph_transform(run.glyph_transform)
+                    .normalized_coords(&coords)
+                    .brush(&run.brush)
+                    .hint(run.hint)
+                    .draw(
+                        &run.style,
+                        run.glyphs.iter().map(|glyph| Glyph {
+                            id: glyph.id,
+                            x: glyph.x,
+                            y: glyph.y,
+                        }),
+                    );
+            }
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use kreuz_mock::{MockBackend, MockWindow, RecordingView};
+use kreuz_ui::{ViewEvent, ViewResponce};
+use kreuz_vello::{make_vello_app, VelloApp, VelloOptions};
+use kreuz_window::{
+    AppResponce, ButtonState, Key, KeyCode, KeyEvent, KeyLocation, Modifiers, MouseButton,
+    ScrollDelta, TouchPhase, WindowEvent, WindowId, WindowParams,
+};
+use peniko::kurbo::{Point, Rect, Size, Vec2};
+use std::time::Duration;
+
+type App = VelloApp<'static, MockWindow, RecordingView>;
+
+fn start(size: Size, scale: f64) -> (MockBackend<App>, WindowId) {
+    let app: App = make_vello_app(|_| RecordingView::new());
+    let mut backend = MockBackend::new(app);
+    let id = backend.create_window(WindowParams {
+        size,
+        scale,
+        ..Default::default()
+    });
+    (backend, id)
+}
+
+fn take_events(backend: &mut MockBackend<App>, id: WindowId) -> Vec<ViewEvent> {
+    backend.app_mut().view_mut(id).unwrap().take_events()
+}
+
+fn key_a() -> KeyEvent {
+    KeyEvent {
+        key: Key::Character("a".into()),
+        code: KeyCode::KeyA,
+        location: KeyLocation::Standard,
+        repeat: false,
+        text: Some("a".into()),
+    }
+}
+
+#[test]
+fn window_without_surface_is_resized() {
+    let (mut backend, id) = start(Size::new(400., 300.), 2.);
+    assert!(backend
+        .take_responces()
+        .iter()
+        .all(|responce| matches!(responce, AppResponce::Handled)));
+    assert_eq!(
+        take_events(&mut backend, id),
+        [ViewEvent::Resize {
+            size: Size::new(400., 300.),
+            scale: 2.,
+        }]
+    );
+}
+
+#[test]
+fn sizes_stay_logical() {
+    let (mut backend, id) = start(Size::new(400., 300.), 1.);
+    take_events(&mut backend, id);
+
+    backend.send_all(
+        id,
+        [
+            WindowEvent::ScaleFactorChanged { scale: 1.5 },
+            WindowEvent::Resize {
+                new_size: Size::new(500., 200.),
+            },
+        ],
+    );
+    assert_eq!(
+        take_events(&mut backend, id),
+        [
+            ViewEvent::Resize {
+                size: Size::new(400., 300.),
+                scale: 1.5,
+            },
+            ViewEvent::Resize {
+                size: Size::new(500., 200.),
+                scale: 1.5,
+            },
+        ]
+    );
+}
+
+#[test]
+fn pointer_events_use_last_cursor_position() {
+    let (mut backend, id) = start(Size::new(400., 300.), 2.);
+    take_events(&mut backend, id);
+
+    let pos = Point::new(10.5, 20.);
+    backend.send_all(
+        id,
+        [
+            WindowEvent::CursorEntered,
+            WindowEvent::CursorMove { pos },
+            WindowEvent::MouseButton {
+                button: MouseButton::Left,
+                state: ButtonState::Pressed,
+            },
+            WindowEvent::MouseButton {
+                button: MouseButton::Left,
+                state: ButtonState::Released,
+            },
+            WindowEvent::MouseWheel {
+                delta: ScrollDelta::Pixels(Vec2::new(0., -3.)),
+                phase: TouchPhase::Moved,
+            },
+            WindowEvent::CursorLeft,
+        ],
+    );
+    assert_eq!(
+        take_events(&mut backend, id),
+        [
+            ViewEvent::CursorEntered,
+            ViewEvent::CursorMove { pos },
+            ViewEvent::MouseButtonPress {
+                pos,
+                button: kreuz_ui::MouseButton::Left,
+            },
+            ViewEvent::MouseButtonRelease {
+                pos,
+                button: kreuz_ui::MouseButton::Left,
+            },
+            ViewEvent::MouseWheel {
+                pos,
+                delta: kreuz_ui::ScrollDelta::Pixels(Vec2::new(0., -3.)),
+                phase: kreuz_ui::TouchPhase::Moved,
+            },
+            ViewEvent::CursorLeft,
+        ]
+    );
+}
+
+#[test]
+fn key_events_carry_current_modifiers() {
+    let (mut backend, id) = start(Size::new(400., 300.), 1.);
+    take_events(&mut backend, id);
+
+    let shift = Modifiers {
+        shift: true,
+        ..Default::default()
+    };
+    backend.send_all(
+        id,
+        [
+            WindowEvent::KeyDown { event: key_a() },
+            WindowEvent::ModifiersChanged { modifiers: shift },
+            WindowEvent::KeyDown { event: key_a() },
+            WindowEvent::ModifiersChanged {
+                modifiers: Modifiers::default(),
+            },
+            WindowEvent::KeyUp { event: key_a() },
+        ],
+    );
+
+    let ui_key_a = |modifiers| kreuz_ui::KeyEvent {
+        key: kreuz_ui::Key::Character("a".into()),
+        code: kreuz_ui::KeyCode::KeyA,
+        location: kreuz_ui::KeyLocation::Standard,
+        repeat: false,
+        text: Some("a".into()),
+        modifiers,
+    };
+    let ui_shift = kreuz_ui::Modifiers {
+        shift: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        take_events(&mut backend, id),
+        [
+            ViewEvent::KeyDown {
+                event: ui_key_a(kreuz_ui::Modifiers::default()),
+            },
+            ViewEvent::ModifiersChanged {
+                modifiers: ui_shift,
+            },
+            ViewEvent::KeyDown {
+                event: ui_key_a(ui_shift),
+            },
+            ViewEvent::ModifiersChanged {
+                modifiers: kreuz_ui::Modifiers::default(),
+            },
+            ViewEvent::KeyUp {
+                event: ui_key_a(kreuz_ui::Modifiers::default()),
+            },
+        ]
+    );
+}
+
+#[test]
+fn skipped_close_request_closes_window() {
+    let (mut backend, id) = start(Size::new(400., 300.), 1.);
+    take_events(&mut backend, id);
+    backend.take_responces();
+
+    backend.send(id, WindowEvent::CloseRequested);
+    assert!(matches!(
+        backend.take_responces()[..],
+        [AppResponce::CloseWindow(closed)] if closed == id
+    ));
+    assert!(backend.window(id).is_none());
+    assert!(backend.app().view(id).is_none());
+}
+
+#[test]
+fn vetoed_close_request_keeps_window() {
+    let (mut backend, id) = start(Size::new(400., 300.), 1.);
+    backend.app_mut().view_mut(id).unwrap().responce = ViewResponce::Handled;
+    backend.take_responces();
+
+    backend.send(id, WindowEvent::CloseRequested);
+    assert!(matches!(
+        backend.take_responces()[..],
+        [AppResponce::Handled]
+    ));
+    assert!(backend.window(id).is_some());
+
+    // Views repaint or animate to show a confirmation, which vetoes the close too
+    for responce in [ViewResponce::Repaint, ViewResponce::Animate] {
+        backend.app_mut().view_mut(id).unwrap().responce = responce;
+        backend.send(id, WindowEvent::CloseRequested);
+        assert!(backend.window(id).is_some());
+    }
+}
+
+#[test]
+fn ime_follows_view_area() {
+    let (mut backend, id) = start(Size::new(400., 300.), 2.);
+    let window = backend.window(id).unwrap().clone();
+    assert!(!window.state().ime_allowed);
+
+    let area = Rect::new(10., 20., 110., 40.);
+    backend.app_mut().view_mut(id).unwrap().ime_area = Some(area);
+    backend.send(id, WindowEvent::Focused { focused: true });
+    let state = window.state();
+    assert!(state.ime_allowed);
+    assert_eq!(state.ime_cursor_area, Some((area.origin(), area.size())));
+
+    backend.app_mut().view_mut(id).unwrap().ime_area = None;
+    backend.send(id, WindowEvent::Focused { focused: false });
+    assert!(!window.state().ime_allowed);
+}
+
+#[test]
+fn animation_frames_follow_clock() {
+    let (mut backend, id) = start(Size::new(400., 300.), 1.);
+    let clock = backend.clock();
+    backend.app_mut().set_clock(move || clock.now());
+    backend.app_mut().view_mut(id).unwrap().responce = ViewResponce::Animate;
+    backend.send(id, WindowEvent::Focused { focused: true });
+    take_events(&mut backend, id);
+
+    let frame = Duration::from_millis(16);
+    backend.advance(frame);
+    backend.advance(frame);
+    assert_eq!(
+        take_events(&mut backend, id),
+        [
+            ViewEvent::AnimationFrame { time: frame },
+            ViewEvent::AnimationFrame { time: frame * 2 },
+        ]
+    );
+
+    // Frames stop once the view doesn't ask for more
+    backend.app_mut().view_mut(id).unwrap().responce = ViewResponce::Handled;
+    backend.advance(frame);
+    backend.advance(frame);
+    assert_eq!(
+        take_events(&mut backend, id),
+        [ViewEvent::AnimationFrame { time: frame * 3 }]
+    );
+}
+
+#[test]
+fn changed_options_redraw_windows() {
+    let (mut backend, id) = start(Size::new(400., 300.), 1.);
+    let window = backend.window(id).unwrap().clone();
+    backend.advance(Duration::ZERO);
+    assert!(!window.state().redraw_pending);
+
+    let handle = backend.app().options_handle();
+    handle.set(handle.get());
+    assert!(!window.state().redraw_pending);
+
+    let options = VelloOptions {
+        profiling: true,
+        ..Default::default()
+    };
+    handle.set(options);
+    assert!(window.state().redraw_pending);
+    backend.advance(Duration::ZERO);
+    assert_eq!(*backend.app().options(), options);
+}
@@ -2,10 +2,14 @@ use super::*;
 
 pub enum AppResponce {
     Handled,
-    Error(Box<dyn std::error::Error>),
+    Error(Box<dyn std::error::Error + Send + Sync>),
     CloseWindow(WindowId),
     CreateNewWindow(WindowParams),
-    CreateNewSubwindow(SubwindowParams),
+    /// Subwindow is positioned relative to the parent surface and closed together with it
+    CreateNewSubwindow {
+        parent: WindowId,
+        params: SubwindowParams,
+    },
 }
 
 pub trait AppHandler<W: WindowHandler, SW: SubwindowHandler> {
@@ -16,6 +20,7 @@ pub trait AppHandler<W: WindowHandler, SW: SubwindowHandler> {
             position: None,
             resizable: true,
             title: "Window".into(),
+            ..Default::default()
         }
     }
 
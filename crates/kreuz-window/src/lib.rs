@@ -1,7 +1,11 @@
 mod application;
+mod ime;
+mod keyboard;
 mod window;
 mod window_event;
 
 pub use application::*;
+pub use ime::*;
+pub use keyboard::*;
 pub use window::*;
 pub use window_event::*;
@@ -4,6 +4,7 @@ use peniko::kurbo::{Point, Size};
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WindowId(usize);
 
 static LAST_ID: AtomicUsize = AtomicUsize::new(0);
@@ -16,14 +17,111 @@ impl WindowId {
     }
 }
 
+/// Sizes and positions are in logical pixels, multiplied by `scale` they give physical pixels
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WindowParams {
     pub size: Size,
+    /// Scale factor of the monitor the window is on, ignored when creating a window
     pub scale: f64,
     /// Position from (0, 0) of parent window's surface
     pub position: Option<Point>,
     pub resizable: bool,
     pub title: String,
+    pub min_size: Option<Size>,
+    pub max_size: Option<Size>,
+    pub enabled_buttons: WindowButtons,
+    pub maximized: bool,
+    pub visible: bool,
+    pub transparent: bool,
+    pub blur: bool,
+    pub decorations: bool,
+    pub window_icon: Option<Icon>,
+    pub preferred_theme: Option<Theme>,
+    /// Window size will be changed by multiples of this value while user resizes it
+    pub resize_increments: Option<Size>,
+    /// Prevents the window contents from being captured by other apps
+    pub content_protected: bool,
+    pub window_level: WindowLevel,
+    pub fullscreen: Option<Fullscreen>,
+}
+
+impl Default for WindowParams {
+    fn default() -> Self {
+        Self {
+            size: (800., 600.).into(),
+            scale: 1.,
+            position: None,
+            resizable: true,
+            title: "Window".into(),
+            min_size: None,
+            max_size: None,
+            enabled_buttons: Default::default(),
+            maximized: false,
+            visible: true,
+            transparent: false,
+            blur: false,
+            decorations: true,
+            window_icon: None,
+            preferred_theme: None,
+            resize_increments: None,
+            content_protected: false,
+            window_level: Default::default(),
+            fullscreen: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowButtons {
+    pub close: bool,
+    pub minimize: bool,
+    pub maximize: bool,
+}
+
+impl Default for WindowButtons {
+    fn default() -> Self {
+        Self {
+            close: true,
+            minimize: true,
+            maximize: true,
+        }
+    }
+}
+
+/// Icon in RGBA8 format, `rgba` length must be equal to `width * height * 4`
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Icon {
+    pub rgba: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WindowLevel {
+    AlwaysOnBottom,
+    #[default]
+    Normal,
+    AlwaysOnTop,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Fullscreen {
+    /// Window covers the whole monitor, video mode stays the same
+    Borderless,
+    /// Window takes exclusive control over the monitor using its best video mode
+    Exclusive,
 }
 
 pub trait WindowHandler: Send + Sync + HasWindowHandle + HasDisplayHandle {
@@ -32,11 +130,53 @@ pub trait WindowHandler: Send + Sync + HasWindowHandle + HasDisplayHandle {
     fn set_title(&self, title: &str);
 
     fn get_params(&self) -> WindowParams;
+
+    fn set_min_size(&self, size: Option<Size>);
+
+    fn set_max_size(&self, size: Option<Size>);
+
+    fn set_resizable(&self, resizable: bool);
+
+    fn set_enabled_buttons(&self, buttons: WindowButtons);
+
+    fn set_maximized(&self, maximized: bool);
+
+    fn set_visible(&self, visible: bool);
+
+    fn set_transparent(&self, transparent: bool);
+
+    fn set_blur(&self, blur: bool);
+
+    fn set_decorations(&self, decorations: bool);
+
+    fn set_window_icon(&self, icon: Option<Icon>);
+
+    fn set_theme(&self, theme: Option<Theme>);
+
+    fn set_resize_increments(&self, increments: Option<Size>);
+
+    fn set_content_protected(&self, protected: bool);
+
+    fn set_window_level(&self, level: WindowLevel);
+
+    fn set_fullscreen(&self, fullscreen: Option<Fullscreen>);
+
+    /// Allows the window to receive [`WindowEvent::Ime`] events
+    ///
+    /// [`WindowEvent::Ime`]: crate::WindowEvent::Ime
+    fn set_ime_allowed(&self, allowed: bool);
+
+    /// Sets the area of the window surface where text is being composed, IME candidate window
+    /// will be placed near it
+    fn set_ime_cursor_area(&self, position: Point, size: Size);
 }
 
+/// Sizes and positions are in logical pixels, like in `WindowParams`
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SubwindowParams {
     pub size: Size,
+    /// Scale factor of the monitor the subwindow is on, ignored when creating a subwindow
     pub scale: f64,
     /// Position from (0, 0) of parent window's surface
     pub position: Point,
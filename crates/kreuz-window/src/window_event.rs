@@ -1,13 +1,46 @@
-use peniko::kurbo::{Point, Size};
+use peniko::kurbo::{Point, Size, Vec2};
 
-#[derive(Debug, Clone)]
+use super::*;
+
+/// Positions, sizes and pixel deltas are in logical pixels
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum WindowEvent {
+    /// Size of the window surface changed
     Resize {
         new_size: Size,
     },
 
     Redraw,
 
+    /// User asked to close the window, it will be closed only if the app responds with
+    /// `AppResponce::CloseWindow`
+    CloseRequested,
+
+    /// Window was moved, position is relative to the top-left corner of the desktop
+    Moved {
+        pos: Point,
+    },
+
+    Focused {
+        focused: bool,
+    },
+
+    /// Window became fully hidden from the user (e.g. minimized or covered by other windows)
+    /// or visible again
+    Occluded {
+        occluded: bool,
+    },
+
+    /// Window moved to a monitor with another scale factor or the monitor's scale changed
+    ScaleFactorChanged {
+        scale: f64,
+    },
+
+    ThemeChanged {
+        theme: Theme,
+    },
+
     CursorEntered,
     CursorLeft,
 
@@ -19,15 +52,68 @@ pub enum WindowEvent {
         button: MouseButton,
         state: ButtonState,
     },
+
+    MouseWheel {
+        delta: ScrollDelta,
+        phase: TouchPhase,
+    },
+
+    /// Touch with a finger, `id` stays the same for the whole lifetime of a touch
+    Touch {
+        id: u64,
+        pos: Point,
+        phase: TouchPhase,
+        /// Normalized pressure in range from 0.0 to 1.0, if the device can measure it
+        force: Option<f64>,
+    },
+
+    /// Two-finger pinch, positive delta means magnification
+    PinchGesture {
+        delta: f64,
+        phase: TouchPhase,
+    },
+
+    /// Two-finger pan
+    PanGesture {
+        delta: Vec2,
+        phase: TouchPhase,
+    },
+
+    /// Two-finger rotation, delta is in degrees, positive value means counterclockwise rotation
+    RotationGesture {
+        delta: f64,
+        phase: TouchPhase,
+    },
+
+    /// Two-finger double tap, used as "smart magnification" on touchpads
+    DoubleTapGesture,
+
+    KeyDown {
+        event: KeyEvent,
+    },
+
+    KeyUp {
+        event: KeyEvent,
+    },
+
+    ModifiersChanged {
+        modifiers: Modifiers,
+    },
+
+    Ime {
+        event: ImeEvent,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ButtonState {
     Pressed,
     Released,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MouseButton {
     Left,
     Right,
@@ -35,3 +121,21 @@ pub enum MouseButton {
     Back,
     Forward,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScrollDelta {
+    /// Amount of lines or rows to scroll, used by traditional mouse wheels
+    Lines(Vec2),
+    /// Amount of pixels to scroll, used by touchpads and other precise devices
+    Pixels(Vec2),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TouchPhase {
+    Started,
+    Moved,
+    Ended,
+    Cancelled,
+}
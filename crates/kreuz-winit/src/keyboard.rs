@@ -0,0 +1,95 @@
+use kreuz_window::{Key, KeyCode, KeyEvent, KeyLocation, Modifiers, NamedKey};
+use winit::{
+    event::KeyEvent as WinitKeyEvent,
+    keyboard::{
+        Key as WinitKey, KeyCode as WinitKeyCode, KeyLocation as WinitKeyLocation, ModifiersState,
+        NamedKey as WinitNamedKey, PhysicalKey,
+    },
+};
+
+macro_rules! map_variants {
+    ($value:expr, $from:ident => $to:ident, [$($variant:ident),* $(,)?]) => {
+        match $value {
+            $($from::$variant => Some($to::$variant),)*
+            _ => None,
+        }
+    };
+}
+
+pub(crate) fn convert_key_event(event: &WinitKeyEvent) -> KeyEvent {
+    KeyEvent {
+        key: convert_key(&event.logical_key),
+        code: convert_physical_key(event.physical_key),
+        location: convert_key_location(event.location),
+        repeat: event.repeat,
+        text: event.text.as_ref().map(|text| text.to_string()),
+    }
+}
+
+pub(crate) fn convert_modifiers(state: ModifiersState) -> Modifiers {
+    Modifiers {
+        shift: state.shift_key(),
+        control: state.control_key(),
+        alt: state.alt_key(),
+        super_key: state.super_key(),
+    }
+}
+
+fn convert_key(key: &WinitKey) -> Key {
+    match key {
+        WinitKey::Named(key) => convert_named_key(*key).map_or(Key::Unidentified, Key::Named),
+        WinitKey::Character(ch) => Key::Character(ch.to_string()),
+        WinitKey::Dead(ch) => Key::Dead(*ch),
+        WinitKey::Unidentified(_) => Key::Unidentified,
+    }
+}
+
+fn convert_key_location(location: WinitKeyLocation) -> KeyLocation {
+    match location {
+        WinitKeyLocation::Standard => KeyLocation::Standard,
+        WinitKeyLocation::Left => KeyLocation::Left,
+        WinitKeyLocation::Right => KeyLocation::Right,
+        WinitKeyLocation::Numpad => KeyLocation::Numpad,
+    }
+}
+
+fn convert_physical_key(key: PhysicalKey) -> KeyCode {
+    match key {
+        PhysicalKey::Code(code) => convert_key_code(code).unwrap_or(KeyCode::Unidentified),
+        PhysicalKey::Unidentified(_) => KeyCode::Unidentified,
+    }
+}
+
+fn convert_named_key(key: WinitNamedKey) -> Option<NamedKey> {
+    map_variants!(key, WinitNamedKey => NamedKey, [
+        Alt, AltGraph, CapsLock, Control, Fn, NumLock, ScrollLock, Shift, Super,
+        Enter, Tab, Space,
+        ArrowDown, ArrowLeft, ArrowRight, ArrowUp, End, Home, PageDown, PageUp,
+        Backspace, Clear, Copy, Cut, Delete, Insert, Paste, Redo, Undo,
+        ContextMenu, Escape, Find, Help, Pause, PrintScreen,
+        MediaPlayPause, MediaStop, MediaTrackNext, MediaTrackPrevious,
+        AudioVolumeDown, AudioVolumeUp, AudioVolumeMute,
+        F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+        F13, F14, F15, F16, F17, F18, F19, F20, F21, F22, F23, F24,
+    ])
+}
+
+fn convert_key_code(code: WinitKeyCode) -> Option<KeyCode> {
+    map_variants!(code, WinitKeyCode => KeyCode, [
+        Backquote, Backslash, BracketLeft, BracketRight, Comma,
+        Digit0, Digit1, Digit2, Digit3, Digit4, Digit5, Digit6, Digit7, Digit8, Digit9,
+        Equal, IntlBackslash,
+        KeyA, KeyB, KeyC, KeyD, KeyE, KeyF, KeyG, KeyH, KeyI, KeyJ, KeyK, KeyL, KeyM,
+        KeyN, KeyO, KeyP, KeyQ, KeyR, KeyS, KeyT, KeyU, KeyV, KeyW, KeyX, KeyY, KeyZ,
+        Minus, Period, Quote, Semicolon, Slash,
+        AltLeft, AltRight, Backspace, CapsLock, ContextMenu, ControlLeft, ControlRight, Enter,
+        SuperLeft, SuperRight, ShiftLeft, ShiftRight, Space, Tab,
+        Delete, End, Home, Insert, PageDown, PageUp, ArrowDown, ArrowLeft, ArrowRight, ArrowUp,
+        NumLock, Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7,
+        Numpad8, Numpad9, NumpadAdd, NumpadDecimal, NumpadDivide, NumpadEnter, NumpadMultiply,
+        NumpadSubtract,
+        Escape, PrintScreen, ScrollLock, Pause,
+        F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+        F13, F14, F15, F16, F17, F18, F19, F20, F21, F22, F23, F24,
+    ])
+}
@@ -1,18 +1,22 @@
+mod keyboard;
 mod subwindow;
 mod window;
 
 pub use subwindow::*;
 pub use window::*;
 
-use anyhow::Result;
-use kreuz_window::{AppHandler, ButtonState, MouseButton, WindowEvent, WindowId, WindowParams};
+use anyhow::{anyhow, Result};
+use kreuz_window::{
+    AppHandler, AppResponce, ButtonState, ImeEvent, MouseButton, ScrollDelta, SubwindowParams,
+    TouchPhase, WindowEvent, WindowId, WindowParams,
+};
 use std::{collections::HashMap, sync::Arc};
 use winit::{
     application::ApplicationHandler,
     dpi::{LogicalPosition, LogicalSize},
     event::{
-        ElementState as WinitElementState, MouseButton as WinitMouseButton,
-        WindowEvent as WinitWindowEvent,
+        ElementState as WinitElementState, Ime as WinitIme, MouseButton as WinitMouseButton,
+        MouseScrollDelta, TouchPhase as WinitTouchPhase, WindowEvent as WinitWindowEvent,
     },
     event_loop::{ActiveEventLoop, EventLoop},
     window::{Window, WindowId as WinitId},
@@ -22,38 +26,123 @@ struct WinitEventLoop<A: AppHandler<WinitWinHandler, WinitSubwinHandler>> {
     app: A,
     windows: HashMap<WinitId, (WindowId, Option<WinitWinHandler>)>,
     subwindows: HashMap<WinitId, (WindowId, Option<WinitSubwinHandler>)>,
+    /// First error reported by the app, the event loop stops after it
+    error: Option<anyhow::Error>,
 }
 
-impl<A: AppHandler<WinitWinHandler, WinitSubwinHandler>> ApplicationHandler for WinitEventLoop<A> {
-    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
-        if self.windows.is_empty() {
-            let window = WinitWinHandler(create_winit_window(
-                event_loop,
-                &self.app.default_window_params(),
-            ));
-            let winit_id = window.0.id();
-            let window_id = WindowId::new();
+impl<A: AppHandler<WinitWinHandler, WinitSubwinHandler>> WinitEventLoop<A> {
+    fn scale_factor(&self, window_id: WinitId) -> f64 {
+        let window = self
+            .windows
+            .get(&window_id)
+            .and_then(|(_, window)| window.as_ref());
+        let subwindow = self
+            .subwindows
+            .get(&window_id)
+            .and_then(|(_, window)| window.as_ref());
+        window
+            .map(|window| window.window.scale_factor())
+            .or_else(|| subwindow.map(|window| window.window.scale_factor()))
+            .unwrap_or(1.)
+    }
 
-            self.app.handle_window_update(window_id, window.clone());
+    fn handle_responce(&mut self, event_loop: &ActiveEventLoop, responce: AppResponce) {
+        match responce {
+            AppResponce::Handled => {}
+            AppResponce::Error(err) => self.exit_with_error(event_loop, anyhow!(err)),
+            AppResponce::CloseWindow(id) => self.close_window(event_loop, id),
+            AppResponce::CreateNewWindow(params) => self.create_window(event_loop, params),
+            AppResponce::CreateNewSubwindow { parent, params } => {
+                self.create_subwindow(event_loop, parent, params);
+            }
+        }
+    }
 
-            self.windows.insert(winit_id, (window_id, Some(window)));
-            return;
+    fn create_window(&mut self, event_loop: &ActiveEventLoop, params: WindowParams) {
+        let window = match create_winit_window(event_loop, &params) {
+            Ok(window) => WinitWinHandler::new(window, params),
+            Err(err) => return self.exit_with_error(event_loop, err),
+        };
+        let window_id = WindowId::new();
+        self.windows
+            .insert(window.window.id(), (window_id, Some(window.clone())));
+
+        let responce = self.app.handle_window_update(window_id, window);
+        self.handle_responce(event_loop, responce);
+    }
+
+    fn create_subwindow(
+        &mut self,
+        event_loop: &ActiveEventLoop,
+        parent_id: WindowId,
+        params: SubwindowParams,
+    ) {
+        let parent = self
+            .windows
+            .values()
+            .find(|(id, _)| *id == parent_id)
+            .and_then(|(_, window)| window.as_ref())
+            .map(|window| window.window.clone());
+        let Some(parent) = parent else {
+            let err = anyhow!("can't create a subwindow of unknown window {parent_id:?}");
+            return self.exit_with_error(event_loop, err);
+        };
+        let window = match create_winit_subwindow(event_loop, &parent, &params) {
+            Ok(window) => WinitSubwinHandler::new(window, parent_id, parent, params),
+            Err(err) => return self.exit_with_error(event_loop, err),
+        };
+        let window_id = WindowId::new();
+        self.subwindows
+            .insert(window.window.id(), (window_id, Some(window.clone())));
+
+        let responce = self.app.handle_subwindow_update(window_id, window);
+        self.handle_responce(event_loop, responce);
+    }
+
+    fn close_window(&mut self, event_loop: &ActiveEventLoop, id: WindowId) {
+        // Winit windows are destroyed when the last handle is dropped
+        self.windows.retain(|_, (window_id, _)| *window_id != id);
+        self.subwindows.retain(|_, (window_id, window)| {
+            let is_child = window.as_ref().is_some_and(|window| window.parent_id == id);
+            *window_id != id && !is_child
+        });
+        if self.windows.is_empty() {
+            event_loop.exit();
         }
+    }
 
-        let default_window_params = self.app.default_window_params();
-        for (_, (id, cached_window)) in &mut self.windows {
-            let window = cached_window.take().unwrap_or_else(|| {
-                WinitWinHandler(create_winit_window(event_loop, &default_window_params))
-            });
+    fn exit_with_error(&mut self, event_loop: &ActiveEventLoop, err: anyhow::Error) {
+        if self.error.is_none() {
+            self.error = Some(err);
+        }
+        event_loop.exit();
+    }
+}
 
-            self.app.handle_window_update(*id, window.clone());
+impl<A: AppHandler<WinitWinHandler, WinitSubwinHandler>> ApplicationHandler for WinitEventLoop<A> {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        if self.windows.is_empty() {
+            let params = self.app.default_window_params();
+            self.create_window(event_loop, params);
+            return;
+        }
 
-            *cached_window = Some(window);
+        let mut responces = Vec::new();
+        for (id, cached_window) in self.windows.values() {
+            let Some(window) = cached_window.clone() else {
+                continue;
+            };
+            responces.push(self.app.handle_window_update(*id, window));
+        }
+        for (id, cached_window) in self.subwindows.values() {
+            let Some(window) = cached_window.clone() else {
+                continue;
+            };
+            responces.push(self.app.handle_subwindow_update(*id, window));
+        }
+        for responce in responces {
+            self.handle_responce(event_loop, responce);
         }
-        // let default_subwindow_params = self.app.default_subwindow_params();
-        // for (win_id, (id, cached_window)) in &mut self.subwindows {
-        //     todo!("Subwindows aren't supported for winit")
-        // }
     }
 
     fn suspended(&mut self, _event_loop: &ActiveEventLoop) {}
@@ -65,21 +154,64 @@ impl<A: AppHandler<WinitWinHandler, WinitSubwinHandler>> ApplicationHandler for
         event: WinitWindowEvent,
     ) {
         macro_rules! pass_event {
-            ($event:expr) => {
-                if let Some((_, (id, _))) = self.windows.get_key_value(&window_id) {
-                    self.app.handle_window_event(*id, $event);
+            ($event:expr) => {{
+                let id = self
+                    .windows
+                    .get(&window_id)
+                    .map(|(id, _)| *id)
+                    .or_else(|| self.subwindows.get(&window_id).map(|(id, _)| *id));
+                if let Some(id) = id {
+                    let responce = self.app.handle_window_event(id, $event);
+                    self.handle_responce(event_loop, responce);
                 }
-                if let Some((_, (id, _))) = self.subwindows.get_key_value(&window_id) {
-                    self.app.handle_window_event(*id, $event);
-                }
-            };
+            }};
         }
 
+        // Winit reports physical pixels, the app gets logical ones
+        let scale = self.scale_factor(window_id);
+
         match event {
-            WinitWindowEvent::CloseRequested => event_loop.exit(),
+            // App can veto closing by not responding with `CloseWindow`
+            WinitWindowEvent::CloseRequested => {
+                pass_event!(WindowEvent::CloseRequested);
+            }
+
+            WinitWindowEvent::Moved(position) => {
+                // Subwindows stay in place relative to the parent surface
+                if let Some((parent_id, _)) = self.windows.get(&window_id) {
+                    self.subwindows
+                        .values()
+                        .filter_map(|(_, window)| window.as_ref())
+                        .filter(|window| window.parent_id == *parent_id)
+                        .for_each(WinitSubwinHandler::follow_parent);
+                }
+                let pos = position.to_logical::<f64>(scale);
+                let pos = (pos.x, pos.y).into();
+                pass_event!(WindowEvent::Moved { pos });
+            }
+
+            WinitWindowEvent::Focused(focused) => {
+                pass_event!(WindowEvent::Focused { focused });
+            }
+
+            WinitWindowEvent::Occluded(occluded) => {
+                pass_event!(WindowEvent::Occluded { occluded });
+            }
+
+            WinitWindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                pass_event!(WindowEvent::ScaleFactorChanged {
+                    scale: scale_factor
+                });
+            }
+
+            WinitWindowEvent::ThemeChanged(theme) => {
+                let theme = from_winit_theme(theme);
+                pass_event!(WindowEvent::ThemeChanged { theme });
+            }
 
             WinitWindowEvent::Resized(size) => {
-                let new_size = (size.width as f64, size.height as f64).into();
+                let size = size.to_logical::<f64>(scale);
+                let new_size = (size.width, size.height).into();
                 pass_event!(WindowEvent::Resize { new_size });
             }
 
@@ -96,6 +228,7 @@ impl<A: AppHandler<WinitWinHandler, WinitSubwinHandler>> ApplicationHandler for
             }
 
             WinitWindowEvent::CursorMoved { position, .. } => {
+                let position = position.to_logical::<f64>(scale);
                 let pos = (position.x, position.y).into();
                 pass_event!(WindowEvent::CursorMove { pos });
             }
@@ -118,6 +251,77 @@ impl<A: AppHandler<WinitWinHandler, WinitSubwinHandler>> ApplicationHandler for
                 }
             }
 
+            WinitWindowEvent::MouseWheel { delta, phase, .. } => {
+                let delta = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => {
+                        ScrollDelta::Lines((x as f64, y as f64).into())
+                    }
+                    MouseScrollDelta::PixelDelta(pos) => {
+                        let pos = pos.to_logical::<f64>(scale);
+                        ScrollDelta::Pixels((pos.x, pos.y).into())
+                    }
+                };
+                let phase = convert_touch_phase(phase);
+                pass_event!(WindowEvent::MouseWheel { delta, phase });
+            }
+
+            WinitWindowEvent::Touch(touch) => {
+                let location = touch.location.to_logical::<f64>(scale);
+                pass_event!(WindowEvent::Touch {
+                    id: touch.id,
+                    pos: (location.x, location.y).into(),
+                    phase: convert_touch_phase(touch.phase),
+                    force: touch.force.map(|force| force.normalized()),
+                });
+            }
+
+            WinitWindowEvent::PinchGesture { delta, phase, .. } => {
+                let phase = convert_touch_phase(phase);
+                pass_event!(WindowEvent::PinchGesture { delta, phase });
+            }
+
+            WinitWindowEvent::PanGesture { delta, phase, .. } => {
+                let delta = delta.to_logical::<f64>(scale);
+                let delta = (delta.x, delta.y).into();
+                let phase = convert_touch_phase(phase);
+                pass_event!(WindowEvent::PanGesture { delta, phase });
+            }
+
+            WinitWindowEvent::RotationGesture { delta, phase, .. } => {
+                let delta = delta as f64;
+                let phase = convert_touch_phase(phase);
+                pass_event!(WindowEvent::RotationGesture { delta, phase });
+            }
+
+            WinitWindowEvent::DoubleTapGesture { .. } => {
+                pass_event!(WindowEvent::DoubleTapGesture);
+            }
+
+            WinitWindowEvent::KeyboardInput { event, .. } => {
+                let state = event.state;
+                let event = keyboard::convert_key_event(&event);
+                let event = match state {
+                    WinitElementState::Pressed => WindowEvent::KeyDown { event },
+                    WinitElementState::Released => WindowEvent::KeyUp { event },
+                };
+                pass_event!(event);
+            }
+
+            WinitWindowEvent::ModifiersChanged(modifiers) => {
+                let modifiers = keyboard::convert_modifiers(modifiers.state());
+                pass_event!(WindowEvent::ModifiersChanged { modifiers });
+            }
+
+            WinitWindowEvent::Ime(ime) => {
+                let event = match ime {
+                    WinitIme::Enabled => ImeEvent::Enabled,
+                    WinitIme::Preedit(text, cursor) => ImeEvent::Preedit { text, cursor },
+                    WinitIme::Commit(text) => ImeEvent::Commit { text },
+                    WinitIme::Disabled => ImeEvent::Disabled,
+                };
+                pass_event!(WindowEvent::Ime { event });
+            }
+
             _ => {}
         }
     }
@@ -129,31 +333,82 @@ pub fn run_with_winit<A: AppHandler<WinitWinHandler, WinitSubwinHandler>>(app: A
         app,
         windows: HashMap::new(),
         subwindows: HashMap::new(),
+        error: None,
     };
 
     // Create and run a winit event loop
     let event_loop = EventLoop::new()?;
-    event_loop
-        .run_app(&mut app)
-        .expect("Couldn't run event loop");
-    Ok(())
+    event_loop.run_app(&mut app)?;
+    match app.error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+fn convert_touch_phase(phase: WinitTouchPhase) -> TouchPhase {
+    match phase {
+        WinitTouchPhase::Started => TouchPhase::Started,
+        WinitTouchPhase::Moved => TouchPhase::Moved,
+        WinitTouchPhase::Ended => TouchPhase::Ended,
+        WinitTouchPhase::Cancelled => TouchPhase::Cancelled,
+    }
 }
 
 /// Helper function that creates a Winit window and returns it (wrapped in an Arc for sharing between threads)
-fn create_winit_window(event_loop: &ActiveEventLoop, window_params: &WindowParams) -> Arc<Window> {
+fn create_winit_window(
+    event_loop: &ActiveEventLoop,
+    window_params: &WindowParams,
+) -> Result<Arc<Window>> {
     let WindowParams {
         size,
         position,
         resizable,
         title,
+        min_size,
+        max_size,
+        enabled_buttons,
+        maximized,
+        visible,
+        transparent,
+        blur,
+        decorations,
+        window_icon,
+        preferred_theme,
+        resize_increments,
+        content_protected,
+        window_level,
+        fullscreen,
         ..
     } = window_params;
-    let mut attr = Window::default_attributes()
-        .with_inner_size(LogicalSize::new(size.width, size.height))
-        .with_resizable(*resizable)
-        .with_title(title);
+    let mut attr =
+        Window::default_attributes()
+            .with_inner_size(LogicalSize::new(size.width, size.height))
+            .with_resizable(*resizable)
+            .with_title(title)
+            .with_enabled_buttons(to_winit_buttons(*enabled_buttons))
+            .with_maximized(*maximized)
+            .with_visible(*visible)
+            .with_transparent(*transparent)
+            .with_blur(*blur)
+            .with_decorations(*decorations)
+            .with_window_icon(window_icon.as_ref().and_then(to_winit_icon))
+            .with_theme(preferred_theme.map(to_winit_theme))
+            .with_content_protected(*content_protected)
+            .with_window_level(to_winit_level(*window_level))
+            .with_fullscreen(fullscreen.and_then(|fullscreen| {
+                to_winit_fullscreen(fullscreen, event_loop.primary_monitor())
+            }));
     if let Some(pos) = position {
-        attr = attr.with_position(LogicalPosition::new(pos.x as u32, pos.y as u32));
+        attr = attr.with_position(LogicalPosition::new(pos.x, pos.y));
+    }
+    if let Some(size) = min_size {
+        attr = attr.with_min_inner_size(LogicalSize::new(size.width, size.height));
+    }
+    if let Some(size) = max_size {
+        attr = attr.with_max_inner_size(LogicalSize::new(size.width, size.height));
+    }
+    if let Some(size) = resize_increments {
+        attr = attr.with_resize_increments(LogicalSize::new(size.width, size.height));
     }
-    Arc::new(event_loop.create_window(attr).unwrap())
+    Ok(Arc::new(event_loop.create_window(attr)?))
 }
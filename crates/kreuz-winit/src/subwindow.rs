@@ -1,29 +1,155 @@
 use anyhow::Result;
-use kreuz_window::{SubwindowHandler, SubwindowParams};
+use kreuz_window::{SubwindowHandler, SubwindowParams, WindowId};
+use peniko::kurbo::Point;
 use raw_window_handle::{
     DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, WindowHandle,
 };
+use std::sync::{Arc, Mutex};
+use winit::{
+    dpi::{LogicalPosition, LogicalSize},
+    event_loop::ActiveEventLoop,
+    window::{Window, WindowAttributes, WindowLevel},
+};
+
 #[derive(Clone)]
-pub struct WinitSubwinHandler;
+pub struct WinitSubwinHandler {
+    pub window: Arc<Window>,
+    pub(crate) parent_id: WindowId,
+    parent: Arc<Window>,
+    params: Arc<Mutex<SubwindowParams>>,
+}
+
+impl WinitSubwinHandler {
+    pub(crate) fn new(
+        window: Arc<Window>,
+        parent_id: WindowId,
+        parent: Arc<Window>,
+        params: SubwindowParams,
+    ) -> Self {
+        Self {
+            window,
+            parent_id,
+            parent,
+            params: Arc::new(Mutex::new(params)),
+        }
+    }
+
+    /// Moves the subwindow so it stays at the same place relative to the parent surface
+    pub(crate) fn follow_parent(&self) {
+        let position = self.params.lock().unwrap().position;
+        if let Some(position) = absolute_position(&self.parent, position) {
+            self.window.set_outer_position(position);
+        }
+    }
+}
 
 impl HasWindowHandle for WinitSubwinHandler {
     fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
-        todo!("Subwindows aren't supported right now")
+        self.window.window_handle()
     }
 }
 
 impl HasDisplayHandle for WinitSubwinHandler {
     fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
-        todo!("Subwindows aren't supported right now")
+        self.window.display_handle()
     }
 }
 
 impl SubwindowHandler for WinitSubwinHandler {
     fn request_redraw(&self) {
-        todo!("Subwindows aren't supported right now")
+        self.window.request_redraw();
     }
 
     fn get_params(&self) -> SubwindowParams {
-        todo!("Subwindows aren't supported right now")
+        let scale = self.window.scale_factor();
+        let size = self.window.inner_size().to_logical::<f64>(scale);
+        let size = (size.width, size.height).into();
+        SubwindowParams {
+            size,
+            scale,
+            ..self.params.lock().unwrap().clone()
+        }
+    }
+}
+
+/// Creates a borderless window on top of the parent, positioned relative to the parent surface.
+///
+/// Child windows are clipped by the parent on most platforms, so the subwindow is created as a
+/// popup owned by the parent where the platform allows it, otherwise it is a top-level window.
+pub(crate) fn create_winit_subwindow(
+    event_loop: &ActiveEventLoop,
+    parent: &Window,
+    params: &SubwindowParams,
+) -> Result<Arc<Window>> {
+    let SubwindowParams { size, position, .. } = params;
+    let mut attr = Window::default_attributes()
+        .with_inner_size(LogicalSize::new(size.width, size.height))
+        .with_decorations(false)
+        .with_resizable(false)
+        .with_active(false)
+        .with_window_level(WindowLevel::AlwaysOnTop);
+    if let Some(position) = absolute_position(parent, *position) {
+        attr = attr.with_position(position);
+    }
+    attr = with_popup_parent(attr, parent)?;
+    Ok(Arc::new(event_loop.create_window(attr)?))
+}
+
+fn absolute_position(parent: &Window, position: Point) -> Option<LogicalPosition<f64>> {
+    let origin = parent
+        .inner_position()
+        .ok()?
+        .to_logical::<f64>(parent.scale_factor());
+    Some(LogicalPosition::new(
+        origin.x + position.x,
+        origin.y + position.y,
+    ))
+}
+
+#[cfg(target_os = "macos")]
+fn with_popup_parent(attr: WindowAttributes, parent: &Window) -> Result<WindowAttributes> {
+    let handle = parent.window_handle()?.as_raw();
+    // SAFETY: the handle belongs to a live window, the subwindow is closed together with it
+    Ok(unsafe { attr.with_parent_window(Some(handle)) })
+}
+
+#[cfg(target_os = "windows")]
+fn with_popup_parent(attr: WindowAttributes, parent: &Window) -> Result<WindowAttributes> {
+    use raw_window_handle::RawWindowHandle;
+    use winit::platform::windows::WindowAttributesExtWindows;
+
+    let attr = attr.with_skip_taskbar(true);
+    match parent.window_handle()?.as_raw() {
+        RawWindowHandle::Win32(handle) => Ok(attr.with_owner_window(handle.hwnd.get())),
+        _ => Ok(attr),
     }
 }
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+))]
+fn with_popup_parent(attr: WindowAttributes, _parent: &Window) -> Result<WindowAttributes> {
+    use winit::platform::x11::{WindowAttributesExtX11, WindowType};
+
+    // Ignored on Wayland, which has no way to position a top-level window
+    Ok(attr
+        .with_x11_window_type(vec![WindowType::PopupMenu])
+        .with_override_redirect(true))
+}
+
+#[cfg(not(any(
+    target_os = "macos",
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+)))]
+fn with_popup_parent(attr: WindowAttributes, _parent: &Window) -> Result<WindowAttributes> {
+    Ok(attr)
+}
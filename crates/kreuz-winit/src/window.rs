@@ -1,52 +1,253 @@
 use anyhow::Result;
-use kreuz_window::{WindowHandler, WindowParams};
+use kreuz_window::{
+    Fullscreen, Icon, Theme, WindowButtons, WindowHandler, WindowLevel, WindowParams,
+};
+use peniko::kurbo::{Point, Size};
 use raw_window_handle::{
     DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, WindowHandle,
 };
-use std::sync::Arc;
-use winit::window::Window;
+use std::sync::{Arc, Mutex};
+use winit::{
+    dpi::{LogicalPosition, LogicalSize},
+    monitor::MonitorHandle,
+    window::{
+        Fullscreen as WinitFullscreen, Icon as WinitIcon, Theme as WinitTheme, Window,
+        WindowButtons as WinitWindowButtons, WindowLevel as WinitWindowLevel,
+    },
+};
 
 #[derive(Clone)]
-pub struct WinitWinHandler(pub Arc<Window>);
+pub struct WinitWinHandler {
+    pub window: Arc<Window>,
+    /// Last requested parameters, used for the ones that winit can't report back. The preferred
+    /// theme is taken from here too, as `Window::theme` reports the theme currently in use
+    params: Arc<Mutex<WindowParams>>,
+}
+
+impl WinitWinHandler {
+    pub fn new(window: Arc<Window>, params: WindowParams) -> Self {
+        Self {
+            window,
+            params: Arc::new(Mutex::new(params)),
+        }
+    }
+
+    fn update_params(&self, f: impl FnOnce(&mut WindowParams)) {
+        f(&mut self.params.lock().unwrap());
+    }
+}
 
 impl HasWindowHandle for WinitWinHandler {
     fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
-        self.0.window_handle()
+        self.window.window_handle()
     }
 }
 
 impl HasDisplayHandle for WinitWinHandler {
     fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
-        self.0.display_handle()
+        self.window.display_handle()
     }
 }
 
 impl WindowHandler for WinitWinHandler {
     fn request_redraw(&self) {
-        self.0.request_redraw();
+        self.window.request_redraw();
     }
 
     fn set_title(&self, title: &str) {
-        self.0.set_title(title);
+        self.window.set_title(title);
     }
 
     fn get_params(&self) -> WindowParams {
-        let size = self.0.inner_size();
-        let size = (size.width as f64, size.height as f64).into();
-        let scale = self.0.scale_factor();
-        let position = self.0.inner_position();
+        let cached = self.params.lock().unwrap().clone();
+        let scale = self.window.scale_factor();
+        let size = self.window.inner_size().to_logical::<f64>(scale);
+        let size = (size.width, size.height).into();
+        let position = self.window.inner_position();
         let position = match position {
-            Ok(pos) => Some((pos.x as f64, pos.y as f64).into()),
+            Ok(pos) => {
+                let pos = pos.to_logical::<f64>(scale);
+                Some((pos.x, pos.y).into())
+            }
             Err(_) => None,
         };
-        let resizable = self.0.is_resizable();
-        let title = self.0.title();
+        let resizable = self.window.is_resizable();
+        let title = self.window.title();
+        let enabled_buttons = from_winit_buttons(self.window.enabled_buttons());
+        let maximized = self.window.is_maximized();
+        let visible = self.window.is_visible().unwrap_or(cached.visible);
+        let decorations = self.window.is_decorated();
+        let resize_increments = self
+            .window
+            .resize_increments()
+            .map(|size| size.to_logical::<f64>(scale))
+            .map(|size| (size.width, size.height).into());
+        let fullscreen = self.window.fullscreen().map(|fullscreen| match fullscreen {
+            WinitFullscreen::Exclusive(_) => Fullscreen::Exclusive,
+            WinitFullscreen::Borderless(_) => Fullscreen::Borderless,
+        });
         WindowParams {
             size,
             scale,
             position,
             resizable,
             title,
+            enabled_buttons,
+            maximized,
+            visible,
+            decorations,
+            resize_increments,
+            fullscreen,
+            ..cached
         }
     }
+
+    fn set_min_size(&self, size: Option<Size>) {
+        self.window
+            .set_min_inner_size(size.map(|size| LogicalSize::new(size.width, size.height)));
+        self.update_params(|params| params.min_size = size);
+    }
+
+    fn set_max_size(&self, size: Option<Size>) {
+        self.window
+            .set_max_inner_size(size.map(|size| LogicalSize::new(size.width, size.height)));
+        self.update_params(|params| params.max_size = size);
+    }
+
+    fn set_resizable(&self, resizable: bool) {
+        self.window.set_resizable(resizable);
+    }
+
+    fn set_enabled_buttons(&self, buttons: WindowButtons) {
+        self.window.set_enabled_buttons(to_winit_buttons(buttons));
+    }
+
+    fn set_maximized(&self, maximized: bool) {
+        self.window.set_maximized(maximized);
+    }
+
+    fn set_visible(&self, visible: bool) {
+        self.window.set_visible(visible);
+        self.update_params(|params| params.visible = visible);
+    }
+
+    fn set_transparent(&self, transparent: bool) {
+        self.window.set_transparent(transparent);
+        self.update_params(|params| params.transparent = transparent);
+    }
+
+    fn set_blur(&self, blur: bool) {
+        self.window.set_blur(blur);
+        self.update_params(|params| params.blur = blur);
+    }
+
+    fn set_decorations(&self, decorations: bool) {
+        self.window.set_decorations(decorations);
+    }
+
+    fn set_window_icon(&self, icon: Option<Icon>) {
+        self.window
+            .set_window_icon(icon.as_ref().and_then(to_winit_icon));
+        self.update_params(|params| params.window_icon = icon);
+    }
+
+    fn set_theme(&self, theme: Option<Theme>) {
+        self.window.set_theme(theme.map(to_winit_theme));
+        self.update_params(|params| params.preferred_theme = theme);
+    }
+
+    fn set_resize_increments(&self, increments: Option<Size>) {
+        self.window.set_resize_increments(
+            increments.map(|size| LogicalSize::new(size.width, size.height)),
+        );
+        self.update_params(|params| params.resize_increments = increments);
+    }
+
+    fn set_content_protected(&self, protected: bool) {
+        self.window.set_content_protected(protected);
+        self.update_params(|params| params.content_protected = protected);
+    }
+
+    fn set_window_level(&self, level: WindowLevel) {
+        self.window.set_window_level(to_winit_level(level));
+        self.update_params(|params| params.window_level = level);
+    }
+
+    fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
+        let monitor = self.window.current_monitor();
+        self.window.set_fullscreen(
+            fullscreen.and_then(|fullscreen| to_winit_fullscreen(fullscreen, monitor)),
+        );
+    }
+
+    fn set_ime_allowed(&self, allowed: bool) {
+        self.window.set_ime_allowed(allowed);
+    }
+
+    fn set_ime_cursor_area(&self, position: Point, size: Size) {
+        self.window.set_ime_cursor_area(
+            LogicalPosition::new(position.x, position.y),
+            LogicalSize::new(size.width, size.height),
+        );
+    }
+}
+
+pub(crate) fn to_winit_buttons(buttons: WindowButtons) -> WinitWindowButtons {
+    let mut result = WinitWindowButtons::empty();
+    result.set(WinitWindowButtons::CLOSE, buttons.close);
+    result.set(WinitWindowButtons::MINIMIZE, buttons.minimize);
+    result.set(WinitWindowButtons::MAXIMIZE, buttons.maximize);
+    result
+}
+
+fn from_winit_buttons(buttons: WinitWindowButtons) -> WindowButtons {
+    WindowButtons {
+        close: buttons.contains(WinitWindowButtons::CLOSE),
+        minimize: buttons.contains(WinitWindowButtons::MINIMIZE),
+        maximize: buttons.contains(WinitWindowButtons::MAXIMIZE),
+    }
+}
+
+pub(crate) fn to_winit_icon(icon: &Icon) -> Option<WinitIcon> {
+    WinitIcon::from_rgba(icon.rgba.clone(), icon.width, icon.height).ok()
+}
+
+pub(crate) fn to_winit_theme(theme: Theme) -> WinitTheme {
+    match theme {
+        Theme::Light => WinitTheme::Light,
+        Theme::Dark => WinitTheme::Dark,
+    }
+}
+
+pub(crate) fn from_winit_theme(theme: WinitTheme) -> Theme {
+    match theme {
+        WinitTheme::Light => Theme::Light,
+        WinitTheme::Dark => Theme::Dark,
+    }
+}
+
+pub(crate) fn to_winit_level(level: WindowLevel) -> WinitWindowLevel {
+    match level {
+        WindowLevel::AlwaysOnBottom => WinitWindowLevel::AlwaysOnBottom,
+        WindowLevel::Normal => WinitWindowLevel::Normal,
+        WindowLevel::AlwaysOnTop => WinitWindowLevel::AlwaysOnTop,
+    }
+}
+
+/// Exclusive fullscreen requires a video mode, so the one with the biggest resolution and refresh
+/// rate of the given monitor is used
+pub(crate) fn to_winit_fullscreen(
+    fullscreen: Fullscreen,
+    monitor: Option<MonitorHandle>,
+) -> Option<WinitFullscreen> {
+    match fullscreen {
+        Fullscreen::Borderless => Some(WinitFullscreen::Borderless(monitor)),
+        Fullscreen::Exclusive => monitor?
+            .video_modes()
+            .max_by_key(|mode| {
+                let size = mode.size();
+                (size.width * size.height, mode.refresh_rate_millihertz())
+            })
+            .map(WinitFullscreen::Exclusive),
+    }
 }
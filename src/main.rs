@@ -1,7 +1,13 @@
+use kreuz_replay::Recorder;
 use kreuz_ui::DummyView;
 use kreuz_vello::make_vello_app;
 use kreuz_winit::run_with_winit;
 
-fn main() {
-    let _ = run_with_winit(make_vello_app(DummyView));
+fn main() -> anyhow::Result<()> {
+    let app = make_vello_app(|_| DummyView);
+    // Input trace for bug reports, replayed with `kreuz_replay::replay`
+    match std::env::var_os("KREUZ_RECORD") {
+        Some(path) => run_with_winit(Recorder::new(app, path)?),
+        None => run_with_winit(app),
+    }
 }